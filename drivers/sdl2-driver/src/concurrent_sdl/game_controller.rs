@@ -5,10 +5,12 @@ use nalgebra::{Vector2, Vector3};
 use sdl2_sys::{
     SDL_GameControllerAxis, SDL_GameControllerButton, SDL_GameControllerGetAxis,
     SDL_GameControllerGetButton, SDL_GameControllerGetNumTouchpadFingers,
-    SDL_GameControllerGetProduct, SDL_GameControllerGetSensorData,
+    SDL_GameControllerGetProduct, SDL_GameControllerGetSensorData, SDL_GameControllerGetSerial,
     SDL_GameControllerGetTouchpadFinger, SDL_GameControllerGetType, SDL_GameControllerGetVendor,
-    SDL_GameControllerHasSensor, SDL_GameControllerName, SDL_GameControllerSetSensorEnabled,
-    SDL_GameControllerType, SDL_SensorType, SDL_STANDARD_GRAVITY,
+    SDL_GameControllerHasSensor, SDL_GameControllerName, SDL_GameControllerRumble,
+    SDL_GameControllerRumbleTriggers, SDL_GameControllerSetLED, SDL_GameControllerSetPlayerIndex,
+    SDL_GameControllerSetSensorEnabled, SDL_GameControllerType, SDL_SensorType,
+    SDL_STANDARD_GRAVITY,
 };
 use sdl2_sys::{
     SDL_GameControllerOpen, SDL_GameControllerUpdate, SDL_IsGameController, SDL_bool,
@@ -90,6 +92,22 @@ impl GameController {
         }
     }
 
+    pub fn get_serial(&self) -> Option<String> {
+        unsafe {
+            let serial = SDL_GameControllerGetSerial(self.0.as_ptr());
+
+            if serial.is_null() {
+                return None;
+            }
+
+            CStr::from_ptr(serial as *const _)
+                .to_str()
+                .ok()
+                .filter(|serial| !serial.is_empty())
+                .map(str::to_owned)
+        }
+    }
+
     pub fn get_axis_state(&self, axis: SDL_GameControllerAxis) -> i16 {
         unsafe { SDL_GameControllerGetAxis(self.0.as_ptr(), axis) }
     }
@@ -126,6 +144,39 @@ impl GameController {
         out
     }
 
+    pub fn rumble(&self, low_frequency: u16, high_frequency: u16, duration_ms: u32) -> Result<()> {
+        if unsafe {
+            SDL_GameControllerRumble(self.0.as_ptr(), low_frequency, high_frequency, duration_ms)
+        } == -1
+        {
+            Err(get_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn rumble_triggers(&self, left: u16, right: u16, duration_ms: u32) -> Result<()> {
+        if unsafe { SDL_GameControllerRumbleTriggers(self.0.as_ptr(), left, right, duration_ms) }
+            == -1
+        {
+            Err(get_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn set_led(&self, red: u8, green: u8, blue: u8) -> Result<()> {
+        if unsafe { SDL_GameControllerSetLED(self.0.as_ptr(), red, green, blue) } == -1 {
+            Err(get_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn set_player_index(&self, player_index: i32) {
+        unsafe { SDL_GameControllerSetPlayerIndex(self.0.as_ptr(), player_index) }
+    }
+
     pub fn get_trigger(&self, left: bool) -> f32 {
         self.get_axis_state(if left {
             SDL_GameControllerAxis::SDL_CONTROLLER_AXIS_TRIGGERLEFT
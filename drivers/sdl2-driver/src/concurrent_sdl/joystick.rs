@@ -1,5 +1,8 @@
+use std::ffi::CStr;
+
 use sdl2_sys::{
-    SDL_JoystickGetDeviceInstanceID, SDL_LockJoysticks, SDL_NumJoysticks, SDL_UnlockJoysticks,
+    SDL_JoystickGetDeviceGUID, SDL_JoystickGetDeviceInstanceID, SDL_JoystickGetGUIDString,
+    SDL_LockJoysticks, SDL_NumJoysticks, SDL_UnlockJoysticks,
 };
 
 use super::get_error;
@@ -19,6 +22,21 @@ pub fn get_instance_id(device_index: SdlDeviceIndex) -> Result<SdlJoystick> {
     }
 }
 
+pub fn get_guid_string(device_index: SdlDeviceIndex) -> String {
+    let mut buffer = [0; 33];
+    unsafe {
+        SDL_JoystickGetGUIDString(
+            SDL_JoystickGetDeviceGUID(device_index as i32),
+            buffer.as_mut_ptr(),
+            buffer.len() as i32,
+        );
+
+        CStr::from_ptr(buffer.as_ptr())
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
 #[must_use = "if unused the Mutex will immediately unlock"]
 pub struct JoystickLockGuard;
 
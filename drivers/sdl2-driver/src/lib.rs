@@ -1,10 +1,14 @@
 use std::{
     collections::HashMap,
-    sync::atomic::{AtomicBool, Ordering},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     time::{Duration, Instant},
 };
 
 use nalgebra::Vector2;
+use parking_lot::Mutex;
 use sdl2_sys::{
     SDL_GameControllerType, SDL_InitSubSystem, SDL_QuitSubSystem, SDL_SensorType,
     SDL_INIT_GAMECONTROLLER,
@@ -16,7 +20,14 @@ use concurrent_sdl::*;
 use game_controller::*;
 use joystick::*;
 use suinput::driver_interface::*;
-use suinput_types::{controller_paths::GameControllerPaths, event::InputComponentEvent, SuPath};
+use suinput_types::{
+    controller_paths::GameControllerPaths,
+    event::{
+        InputComponentEvent, OutputComponentEvent, OutputEvent, TouchPoint, TouchpadState,
+        MAX_TOUCH_POINTS,
+    },
+    SuPath,
+};
 
 /*
     TODO sort out the controller dupe bug when using winit
@@ -47,8 +58,14 @@ use suinput_types::{controller_paths::GameControllerPaths, event::InputComponent
 pub struct SDLGameControllerGenericDriver {
     interface: RuntimeInterface,
     destroyed: AtomicBool,
+    //Output events are applied on the controller thread, which owns the device id mapping
+    output_events: Arc<Mutex<Vec<OutputEvent>>>,
+    //The last event sent for each component of each device
+    component_states: Arc<Mutex<ComponentStates>>,
 }
 
+type ComponentStates = HashMap<u64, HashMap<SuPath, InputComponentEvent>>;
+
 impl SDLGameControllerGenericDriver {
     ///Should be called from the main thread
     pub fn new(background: bool, interface: RuntimeInterface) -> core::result::Result<Self, ()> {
@@ -79,6 +96,8 @@ impl SDLGameControllerGenericDriver {
         Ok(Self {
             destroyed: AtomicBool::new(false),
             interface,
+            output_events: Default::default(),
+            component_states: Default::default(),
         })
     }
 
@@ -101,10 +120,14 @@ impl SDLGameControllerGenericDriver {
 impl SuInputDriver for SDLGameControllerGenericDriver {
     fn initialize(&mut self) {
         let interface = self.interface.clone();
+        let output_events = self.output_events.clone();
+        let component_states = self.component_states.clone();
         std::thread::spawn(|| {
             let mut thread_state = ThreadState {
                 paths: GameControllerPaths::new(|str| interface.get_path(str).unwrap()),
                 interface,
+                output_events,
+                component_states,
                 old_joysticks: Vec::new(),
                 game_controllers: HashMap::new(),
             };
@@ -122,8 +145,16 @@ impl SuInputDriver for SDLGameControllerGenericDriver {
         todo!()
     }
 
-    fn get_component_state(&self, _device: usize, _path: suinput_types::SuPath) {
-        todo!()
+    fn get_component_state(&self, device: u64, path: SuPath) -> Option<InputComponentEvent> {
+        self.component_states
+            .lock()
+            .get(&device)
+            .and_then(|states| states.get(&path))
+            .copied()
+    }
+
+    fn handle_output_event(&self, event: OutputEvent) {
+        self.output_events.lock().push(event);
     }
 
     fn destroy(&mut self) {
@@ -139,6 +170,8 @@ impl Drop for SDLGameControllerGenericDriver {
 
 struct ThreadState {
     interface: RuntimeInterface,
+    output_events: Arc<Mutex<Vec<OutputEvent>>>,
+    component_states: Arc<Mutex<ComponentStates>>,
     paths: GameControllerPaths,
     old_joysticks: Vec<SdlJoystick>,
     game_controllers: HashMap<SdlJoystick, (ControllerDevice, DeviceState)>,
@@ -153,10 +186,29 @@ impl ThreadState {
 
         // let lock_time = Instant::now();
 
+        let mut component_states = self.component_states.lock();
+
         for (controller, state) in self.game_controllers.values_mut() {
             if controller.sdl.get_type() == SDL_GameControllerType::SDL_CONTROLLER_TYPE_PS5 {
                 controller.update_idx += 1;
-                state.update(controller, &self.interface, &self.paths);
+                state.update(
+                    controller,
+                    &self.interface,
+                    &self.paths,
+                    component_states.entry(controller.idx.unwrap()).or_default(),
+                );
+            }
+        }
+
+        std::mem::drop(component_states);
+
+        for event in self.output_events.lock().drain(..) {
+            if let Some((controller, _)) = self
+                .game_controllers
+                .values_mut()
+                .find(|(controller, _)| controller.idx == Some(event.device))
+            {
+                controller.handle_output_event(event, &self.paths);
             }
         }
     }
@@ -178,17 +230,45 @@ impl ThreadState {
                 .iter()
                 .any(|instance_id| !new_joysticks.contains(instance_id))
         {
+            let interface = &self.interface;
+            let mut component_states = self.component_states.lock();
             self.game_controllers
-                .retain(|joystick_id, _| new_joysticks.contains(joystick_id));
+                .retain(|joystick_id, (controller, _)| {
+                    let connected = new_joysticks.contains(joystick_id);
+                    if !connected {
+                        if let Some(idx) = controller.idx {
+                            interface.disconnect_device(idx).unwrap();
+                            component_states.remove(&idx);
+                        }
+                    }
+                    connected
+                });
+            std::mem::drop(component_states);
 
             for (device_index, joystick) in new_joysticks.iter().enumerate() {
                 if !self.game_controllers.contains_key(joystick) {
                     let device_index = device_index as u32;
                     if is_game_controller(device_index) {
+                        //Identical controllers without serial numbers are told apart by the lowest slot not taken by a connected one
+                        let guid = get_guid_string(device_index);
+                        let slot = (0..)
+                            .find(|slot| {
+                                !self.game_controllers.values().any(|(controller, _)| {
+                                    controller.guid == guid && controller.slot == *slot
+                                })
+                            })
+                            .unwrap();
+
                         self.game_controllers.insert(
-                            device_index,
+                            *joystick,
                             (
-                                ControllerDevice::new(device_index, &self.interface, &self.paths),
+                                ControllerDevice::new(
+                                    device_index,
+                                    guid,
+                                    slot,
+                                    &self.interface,
+                                    &self.paths,
+                                ),
                                 DeviceState::default(),
                             ),
                         );
@@ -208,18 +288,26 @@ struct ControllerDevice {
     has_accel: bool,
     has_touchpad: bool,
 
+    guid: String,
+    slot: usize,
     idx: Option<u64>,
 
     start: SuPath,
     back: SuPath,
     misc1: SuPath,
 
+    //SDL sets both motors at once so the last amplitude of each is kept
+    rumble: [u16; 2],
+    trigger_rumble: [u16; 2],
+
     update_idx: usize,
 }
 
 impl ControllerDevice {
     pub fn new(
         device_index: SdlDeviceIndex,
+        guid: String,
+        slot: usize,
         interface: &RuntimeInterface,
         paths: &GameControllerPaths,
     ) -> Self {
@@ -241,10 +329,12 @@ impl ControllerDevice {
 
         println!("{} connected", sdl.get_name());
 
+        let identity = sdl.get_serial().unwrap_or_else(|| format!("{guid}/{slot}"));
+
         let idx = if sdl.get_type() == SDL_GameControllerType::SDL_CONTROLLER_TYPE_PS5 {
             Some(
                 interface
-                    .register_new_device(paths.device_dual_sense)
+                    .register_new_device(paths.device_dual_sense, Some(&identity))
                     .unwrap(),
             )
         } else {
@@ -258,12 +348,65 @@ impl ControllerDevice {
             has_accel,
             idx,
             has_touchpad,
+            guid,
+            slot,
             back: paths.create,
             start: paths.options,
             misc1: paths.mute,
+            rumble: [0; 2],
+            trigger_rumble: [0; 2],
             update_idx: 0,
         }
     }
+
+    fn handle_output_event(&mut self, event: OutputEvent, paths: &GameControllerPaths) {
+        let result = match event.data {
+            OutputComponentEvent::Vibration {
+                amplitude,
+                duration,
+            } => {
+                let amplitude = (amplitude.clamp(0., 1.) * u16::MAX as f32) as u16;
+                let duration = duration.as_millis().try_into().unwrap_or(u32::MAX);
+
+                if event.path == paths.haptic_left || event.path == paths.rumble_left {
+                    self.rumble[0] = amplitude;
+                } else if event.path == paths.haptic_right || event.path == paths.rumble_right {
+                    self.rumble[1] = amplitude;
+                } else if event.path == paths.left_trigger_rumble {
+                    self.trigger_rumble[0] = amplitude;
+                } else if event.path == paths.right_trigger_rumble {
+                    self.trigger_rumble[1] = amplitude;
+                }
+
+                if event.path == paths.left_trigger_rumble
+                    || event.path == paths.right_trigger_rumble
+                {
+                    self.sdl.rumble_triggers(
+                        self.trigger_rumble[0],
+                        self.trigger_rumble[1],
+                        duration,
+                    )
+                } else {
+                    self.sdl.rumble(self.rumble[0], self.rumble[1], duration)
+                }
+            }
+            OutputComponentEvent::Led(color) => self.sdl.set_led(
+                (color.x.clamp(0., 1.) * 255.) as u8,
+                (color.y.clamp(0., 1.) * 255.) as u8,
+                (color.z.clamp(0., 1.) * 255.) as u8,
+            ),
+            OutputComponentEvent::PlayerNumber(number) => {
+                self.sdl.set_player_index(number as i32);
+                Ok(())
+            }
+            //TODO needs SDL_GameControllerSendEffect which sdl2-sys 0.35 doesn't expose
+            OutputComponentEvent::AdaptiveTrigger(_) => Ok(()),
+        };
+
+        if let Err(err) = result {
+            log::warn!("Failed to apply output event {:?}: {}", event, err);
+        }
+    }
 }
 
 enum ControllerType {
@@ -312,8 +455,7 @@ struct DeviceState {
     left_trigger: f32,
     right_trigger: f32,
 
-    touchpad_1: TouchpadFinger,
-    touchpad_2: TouchpadFinger,
+    touchpad: [TouchpadFinger; MAX_TOUCH_POINTS],
 }
 
 impl DeviceState {
@@ -322,11 +464,16 @@ impl DeviceState {
         device: &ControllerDevice,
         interface: &RuntimeInterface,
         paths: &GameControllerPaths,
+        component_states: &mut HashMap<SuPath, InputComponentEvent>,
     ) {
         use sdl2_sys::SDL_GameControllerButton as Button;
 
         let mut batch_update =
             interface.start_batch_input_update(device.idx.unwrap(), Instant::now());
+        let mut add_event = |path, event| {
+            batch_update.add_event(path, event);
+            component_states.insert(path, event);
+        };
 
         for button_idx in 0..SDL_BUTTON_NUM {
             let (button, path) = match button_idx {
@@ -368,14 +515,14 @@ impl DeviceState {
 
             let state = device.sdl.get_button(button);
             if state != self.buttons[button_idx] {
-                batch_update.add_event(path, InputComponentEvent::Button(state));
+                add_event(path, InputComponentEvent::Button(state));
                 self.buttons[button_idx] = state;
             }
         }
 
         let left_thumbstick = device.sdl.get_thumbstick(true);
         if left_thumbstick != self.left_thumbstick {
-            batch_update.add_event(
+            add_event(
                 paths.left_joystick,
                 InputComponentEvent::Joystick(left_thumbstick.into()),
             );
@@ -384,7 +531,7 @@ impl DeviceState {
 
         let right_thumbstick = device.sdl.get_thumbstick(false);
         if right_thumbstick != self.right_thumbstick {
-            batch_update.add_event(
+            add_event(
                 paths.right_joystick,
                 InputComponentEvent::Joystick(right_thumbstick.into()),
             );
@@ -393,7 +540,7 @@ impl DeviceState {
 
         let left_trigger = device.sdl.get_trigger(true);
         if left_trigger != self.left_trigger {
-            batch_update.add_event(
+            add_event(
                 paths.left_trigger,
                 InputComponentEvent::Trigger(left_trigger),
             );
@@ -402,7 +549,7 @@ impl DeviceState {
 
         let right_trigger = device.sdl.get_trigger(false);
         if right_trigger != self.right_trigger {
-            batch_update.add_event(
+            add_event(
                 paths.right_trigger,
                 InputComponentEvent::Trigger(right_trigger),
             );
@@ -411,24 +558,35 @@ impl DeviceState {
 
         if device.has_gyro {
             let gyro = device.sdl.get_gyro_state().unwrap();
-            batch_update.add_event(paths.gyro, InputComponentEvent::Gyro(gyro.into()));
+            add_event(paths.gyro, InputComponentEvent::Gyro(gyro.into()));
         }
 
         if device.has_accel {
             let accel = device.sdl.get_accel_state().unwrap();
-            batch_update.add_event(paths.accel, InputComponentEvent::Accel(accel.into()));
+            add_event(paths.accel, InputComponentEvent::Accel(accel.into()));
         }
 
-        //TODO
         if device.has_touchpad {
-            let point_1 = device.sdl.get_touchpad_finger(0, 0);
-            if point_1 != self.touchpad_1 {
-                self.touchpad_1 = point_1;
+            let fingers = (device.sdl.get_num_touchpad_fingers(0) as usize).min(MAX_TOUCH_POINTS);
+
+            let mut touchpad = [TouchpadFinger::default(); MAX_TOUCH_POINTS];
+            for (finger, state) in touchpad.iter_mut().enumerate().take(fingers) {
+                *state = device.sdl.get_touchpad_finger(0, finger as u32);
             }
 
-            let point_2 = device.sdl.get_touchpad_finger(0, 1);
-            if point_2 != self.touchpad_2 {
-                self.touchpad_2 = point_2;
+            if touchpad != self.touchpad {
+                let mut state = TouchpadState::default();
+                for (point, finger) in state.points.iter_mut().zip(&touchpad) {
+                    //The DualSense touchpad doesn't report pressure
+                    *point = TouchPoint {
+                        down: finger.down,
+                        position: Vector2::new(finger.x, finger.y).into(),
+                        pressure: None,
+                    };
+                }
+
+                add_event(paths.touchpad, InputComponentEvent::Touchpad(state));
+                self.touchpad = touchpad;
             }
         }
 
@@ -1,7 +1,10 @@
 use std::{ops::Deref, thread::JoinHandle};
 
 use suinput::driver_interface::{RuntimeInterface, SuInputDriver};
-use suinput_types::SuPath;
+use suinput_types::{
+    event::{InputComponentEvent, OutputEvent},
+    SuPath,
+};
 use windows_sys::Win32::Foundation::GetLastError;
 
 //TODO replace with hid_cm
@@ -88,10 +91,12 @@ impl SuInputDriver for Win32HookingWindowDriver {
         todo!()
     }
 
-    fn get_component_state(&self, _device: usize, _path: SuPath) {
-        todo!()
+    fn get_component_state(&self, _device: u64, _path: SuPath) -> Option<InputComponentEvent> {
+        None
     }
 
+    fn handle_output_event(&self, _event: OutputEvent) {}
+
     // fn set_windows(&mut self, windows: &[usize]) {
     //     if let Some(hooks) = &mut self.hooks {
     //         hooks.set_windows(windows).unwrap();
@@ -123,9 +128,13 @@ impl SuInputDriver for Win32RawInputGenericDriver {
 
     fn poll(&self) {}
 
-    fn get_component_state(&self, _device: usize, _path: SuPath) {
-        todo!()
+    //Raw input only reports changes so the runtime's own state is all there is
+    fn get_component_state(&self, _device: u64, _path: SuPath) -> Option<InputComponentEvent> {
+        None
     }
 
+    //Mice and keyboards have no output components
+    fn handle_output_event(&self, _event: OutputEvent) {}
+
     fn destroy(&mut self) {}
 }
@@ -4,6 +4,8 @@ use suinput_types::SuPath;
 pub struct CommonPaths {
     pub mouse: SuPath,
     pub keyboard: SuPath,
+    pub system_cursor: SuPath,
+    pub cursor_point: SuPath,
     pub mouse_move: SuPath,
     pub mouse_scroll: SuPath,
     pub mouse_right_click: SuPath,
@@ -22,6 +24,10 @@ impl CommonPaths {
             keyboard: driver_manager
                 .get_path("/devices/standard/hid_keyboard")
                 .unwrap(),
+            system_cursor: driver_manager
+                .get_path("/devices/standard/system_cursor")
+                .unwrap(),
+            cursor_point: driver_manager.get_path("/input/cursor/point").unwrap(),
             mouse_move: driver_manager.get_path("/input/move/move2d").unwrap(),
             mouse_scroll: driver_manager.get_path("/input/scroll/move2d").unwrap(),
             mouse_right_click: driver_manager
@@ -6,14 +6,18 @@ use std::{
 
 use suinput::driver_interface::RuntimeInterfaceTrait;
 use suinput_types::{
-    event::{InputComponentEvent, InputEvent},
+    event::{Cursor, InputComponentEvent, InputEvent},
     keyboard::{HIDScanCode, KeyboardPaths},
-    Time,
+    Time, WindowHandle,
 };
 use windows_sys::Win32::{
     Devices::HumanInterfaceDevice::MOUSE_MOVE_ABSOLUTE,
-    Foundation::{HANDLE, HWND},
-    System::{LibraryLoader::GetModuleHandleW, SystemInformation::GetTickCount},
+    Foundation::{HANDLE, HWND, POINT},
+    Graphics::Gdi::ScreenToClient,
+    System::{
+        LibraryLoader::GetModuleHandleW, SystemInformation::GetTickCount,
+        Threading::GetCurrentProcessId,
+    },
     UI::{
         Input::{RAWINPUT, RIM_TYPEKEYBOARD, RIM_TYPEMOUSE},
         WindowsAndMessaging::*,
@@ -79,6 +83,8 @@ struct RawInputDriver<'a> {
     keyboard_states: HashMap<HANDLE, HashSet<HIDScanCode>>,
     system_mouse_id: u64,
     system_keyboard_id: u64,
+    system_cursor_id: u64,
+    last_cursor: Option<Cursor>,
 }
 
 impl<'a> RawInputDriver<'a> {
@@ -92,7 +98,13 @@ impl<'a> RawInputDriver<'a> {
                 RIDeviceInfo::GenericHID { .. } => todo!(),
             };
 
-            let device_id = self.driver_manager.register_new_device(device_type);
+            let identity = raw_input::get_rid_device_interface_name(raw_input_device)
+                .ok()
+                .map(|name| name.to_string_lossy().into_owned());
+
+            let device_id = self
+                .driver_manager
+                .register_new_device(device_type, identity.as_deref());
 
             self.device_ids.insert(raw_input_device, device_id.unwrap());
             self.ri_devices.insert(raw_input_device, rid_device_info);
@@ -119,6 +131,7 @@ impl<'a> RawInputDriver<'a> {
                     })
                     .unwrap_or(&self.system_mouse_id);
                 process_mouse(raw_input_data, device_id, self.driver_manager, &self.paths);
+                self.update_cursor();
             }
             RIM_TYPEKEYBOARD => {
                 let device_id = *device_id
@@ -136,6 +149,28 @@ impl<'a> RawInputDriver<'a> {
         }
     }
 
+    //Raw input only carries relative motion so the cursor is sampled after every mouse event
+    //This misses the cursor being moved by another program until the mouse is next touched
+    fn update_cursor(&mut self) {
+        let cursor = get_cursor();
+        if cursor == self.last_cursor {
+            return;
+        }
+        self.last_cursor = cursor;
+
+        //Cursor components have no way to express leaving the window so we stay on the last point
+        if let Some(cursor) = cursor {
+            self.driver_manager
+                .send_component_event(InputEvent {
+                    device: self.system_cursor_id,
+                    path: self.paths.cursor_point,
+                    time: Time(0),
+                    data: InputComponentEvent::Cursor(cursor),
+                })
+                .unwrap();
+        }
+    }
+
     fn process_keyboard(
         &mut self,
         device_id: u64,
@@ -212,8 +247,16 @@ pub fn run(driver_manager: &dyn RuntimeInterfaceTrait) {
         device_ids: HashMap::new(),
         ri_devices: HashMap::new(),
         keyboard_states: HashMap::new(),
-        system_mouse_id: driver_manager.register_new_device(paths.mouse).unwrap(),
-        system_keyboard_id: driver_manager.register_new_device(paths.keyboard).unwrap(),
+        system_mouse_id: driver_manager
+            .register_new_device(paths.mouse, None)
+            .unwrap(),
+        system_keyboard_id: driver_manager
+            .register_new_device(paths.keyboard, None)
+            .unwrap(),
+        system_cursor_id: driver_manager
+            .register_new_device(paths.system_cursor, None)
+            .unwrap(),
+        last_cursor: None,
         paths,
         keyboard_paths,
         driver_manager,
@@ -248,6 +291,49 @@ pub fn run(driver_manager: &dyn RuntimeInterfaceTrait) {
     }
 }
 
+/// The cursor position within the client area of the top level window under it
+///
+/// Windows belonging to other processes are ignored
+fn get_cursor() -> Option<Cursor> {
+    unsafe {
+        let mut point: POINT = std::mem::zeroed();
+        if GetCursorPos(&mut point) == 0 {
+            return None;
+        }
+
+        let window = GetAncestor(WindowFromPoint(point), GA_ROOT);
+        if window == 0 {
+            return None;
+        }
+
+        let mut process_id = 0;
+        GetWindowThreadProcessId(window, &mut process_id);
+        if process_id != GetCurrentProcessId() {
+            return None;
+        }
+
+        let mut client_rect = std::mem::zeroed();
+        if GetClientRect(window, &mut client_rect) == 0 || ScreenToClient(window, &mut point) == 0 {
+            return None;
+        }
+
+        let width = client_rect.right - client_rect.left - 1;
+        let height = client_rect.bottom - client_rect.top - 1;
+        if width <= 0 || height <= 0 {
+            return None;
+        }
+
+        Some(Cursor {
+            normalized_window_coords: [
+                point.x as f64 / width as f64,
+                point.y as f64 / height as f64,
+            ]
+            .into(),
+            window: WindowHandle::new(window as usize)?,
+        })
+    }
+}
+
 pub fn process_mouse(
     raw_input_data: RAWINPUT,
     device_id: u64,
@@ -52,9 +52,12 @@ pub enum ParentActionType {
 pub enum ActionTypeEnum {
     Boolean,
     Delta2d,
+    Cursor,
     Value,
     Axis1d,
     Axis2d,
+    Pose,
+    Output,
 }
 
 impl Action {
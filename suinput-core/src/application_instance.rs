@@ -1,11 +1,12 @@
 use std::fs::File;
 use std::num::NonZeroU128;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Weak};
 
 use parking_lot::{Mutex, RwLock};
 use slotmap::DefaultKey;
 
+use crate::internal::binding::binding_engine::processed_binding_layout::ProcessedBindingLayout;
 use crate::internal::serial;
 use crate::internal::types::HashMap;
 use crate::user::OutActionStateEnum;
@@ -19,6 +20,7 @@ use crate::{
     types::{Error, Result},
     user::User,
 };
+use suinput_types::{binding::Binding, SuPath};
 
 pub struct ApplicationInstance {
     pub(crate) runtime: Weak<Runtime>,
@@ -27,6 +29,7 @@ pub struct ApplicationInstance {
 
     pub(crate) application_name: String,
     pub(crate) sub_name: Option<String>,
+    pub(crate) unique_id: NonZeroU128,
 
     pub(crate) action_sets: HashMap<u64, Arc<ActionSet>>,
     pub(crate) actions: HashMap<u64, Arc<Action>>,
@@ -37,16 +40,18 @@ pub struct ApplicationInstance {
 }
 
 impl ApplicationInstance {
+    /// Derived from the application name and sub name, so it stays the same across restarts and updates
+    pub fn get_persistent_unique_id(&self) -> NonZeroU128 {
+        self.unique_id
+    }
+
     pub fn create_session(self: &Arc<Self>) -> Arc<Session> {
+        //Reads the user's binding overrides so it's done before the session is locked
+        let binding_layouts = self.load_binding_layouts();
+
         let mut lock = self.session.write();
         assert!(lock.is_none());
 
-        let binding_layouts = self
-            .binding_layouts
-            .iter()
-            .map(|layout| (layout.interaction_profile, layout.processed_cache.clone()))
-            .collect();
-
         let user = User {
             action_states: RwLock::new(
                 self.actions
@@ -78,6 +83,13 @@ impl ApplicationInstance {
         });
 
         *lock = Some(Arc::downgrade(&session));
+        drop(lock);
+
+        if let Some(file_path) = self.manifest_path() {
+            if let Err(err) = self.make_persistent(&file_path) {
+                log::warn!("SuInput: failed to write the action manifest with {err}");
+            }
+        }
 
         let handle = runtime.sessions.write().insert(session.clone());
         runtime
@@ -88,6 +100,157 @@ impl ApplicationInstance {
         session
     }
 
+    /// Saves the binding layout as the user's override for its interaction profile, to be layered over the defaults by future sessions
+    ///
+    /// Default bindings missing from the layout are saved alongside it as removed
+    pub fn save_binding_override(&self, binding_layout: &BindingLayout) -> Result<()> {
+        let interaction_profile = binding_layout.interaction_profile;
+        let file_path = self
+            .binding_override_path(interaction_profile)
+            .ok_or(Error::ValidationFailure)?;
+        let removed_path = removed_bindings_path(&file_path);
+
+        let instance = self.instance.upgrade().unwrap();
+
+        let saved = keyed(&instance, binding_layout.bindings.iter());
+        let removed = removed_bindings(
+            keyed(&instance, self.default_bindings(interaction_profile)),
+            &saved,
+        )
+        .into_iter()
+        .cloned()
+        .collect::<Vec<_>>();
+
+        if removed.is_empty() {
+            remove_file(&removed_path)?;
+        } else {
+            let removed = instance
+                .create_binding_layout(&binding_layout.name, interaction_profile, &removed)
+                .map_err(|_| Error::ValidationFailure)?;
+            instance.save_binding_layout(&removed, &removed_path)?;
+        }
+
+        instance.save_binding_layout(binding_layout, &file_path)
+    }
+
+    /// Reverts the interaction profile to the default binding layout in future sessions
+    pub fn remove_binding_override(&self, interaction_profile: SuPath) -> Result<()> {
+        let file_path = self
+            .binding_override_path(interaction_profile)
+            .ok_or(Error::ValidationFailure)?;
+
+        remove_file(&removed_bindings_path(&file_path))?;
+        remove_file(&file_path)
+    }
+
+    fn default_bindings(&self, interaction_profile: SuPath) -> impl Iterator<Item = &Binding> {
+        self.binding_layouts
+            .iter()
+            .filter(move |layout| layout.interaction_profile == interaction_profile)
+            .flat_map(|layout| layout.bindings.iter())
+    }
+
+    /// `<storage_path>/manifests/<unique_id>.json`, lets external tools discover the actions and default bindings
+    fn manifest_path(&self) -> Option<PathBuf> {
+        let instance = self.instance.upgrade().unwrap();
+
+        Some(
+            instance
+                .storage_path
+                .as_ref()?
+                .join("manifests")
+                .join(format!("{:032x}.json", self.unique_id)),
+        )
+    }
+
+    /// `<storage_path>/binding_overrides/<unique_id>/<interaction_profile>.kdl`, the removed defaults are stored next to it in
+    /// `<interaction_profile>.removed.kdl`
+    fn binding_override_path(&self, interaction_profile: SuPath) -> Option<PathBuf> {
+        let instance = self.instance.upgrade().unwrap();
+        let interaction_profile = instance.get_path_string(interaction_profile)?;
+
+        let mut file_path = instance
+            .storage_path
+            .as_ref()?
+            .join("binding_overrides")
+            .join(format!("{:032x}", self.unique_id));
+        file_path.extend(
+            interaction_profile
+                .split('/')
+                .filter(|part| !part.is_empty()),
+        );
+        file_path.set_extension("kdl");
+
+        Some(file_path)
+    }
+
+    /// The default binding layouts with the user's overrides layered on top
+    ///
+    /// Defaults the user removed are dropped, any other defaults missing from the override are kept so that bindings
+    /// added by an application update still show up
+    fn load_binding_layouts(&self) -> HashMap<SuPath, Option<ProcessedBindingLayout>> {
+        let mut binding_layouts: HashMap<SuPath, Option<ProcessedBindingLayout>> = self
+            .binding_layouts
+            .iter()
+            .map(|layout| {
+                (
+                    layout.interaction_profile,
+                    Some(layout.processed_cache.clone()),
+                )
+            })
+            .collect();
+
+        let instance = self.instance.upgrade().unwrap();
+        let runtime = self.runtime.upgrade().unwrap();
+
+        for interaction_profile in runtime.interaction_profile_types.iter() {
+            let interaction_profile = interaction_profile.id;
+
+            let file_path = match self.binding_override_path(interaction_profile) {
+                Some(file_path) if file_path.exists() => file_path,
+                _ => continue,
+            };
+
+            //The error has already been logged, fall back to the defaults
+            let Ok(binding_override) = instance.load_binding_layout(&file_path) else {
+                continue;
+            };
+
+            let removed_path = removed_bindings_path(&file_path);
+            let removed = if removed_path.exists() {
+                let Ok(removed) = instance.load_binding_layout(&removed_path) else {
+                    continue;
+                };
+                removed
+                    .bindings
+                    .iter()
+                    .map(|binding| serial::serialize_binding(&instance, binding))
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            let bindings = layer_bindings(
+                keyed(&instance, self.default_bindings(interaction_profile)),
+                &removed,
+                keyed(&instance, binding_override.bindings.iter()),
+            )
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>();
+
+            if let Ok(layout) = instance.create_binding_layout(
+                &binding_override.name,
+                interaction_profile,
+                &bindings,
+            ) {
+                binding_layouts.insert(interaction_profile, Some(layout.processed_cache.clone()));
+            }
+        }
+
+        binding_layouts
+    }
+
     pub fn make_persistent(&self, file_path: &Path) -> Result<()> {
         std::fs::create_dir_all(file_path.parent().ok_or(Error::ValidationFailure)?)
             .map_err(|_| Error::IoError)?;
@@ -102,17 +265,160 @@ impl ApplicationInstance {
 
     //This is a terrible way of doing this
     pub fn serialize(&self) -> serial::ApplicationInstance {
+        let instance = self.instance.upgrade().unwrap();
+
         serial::ApplicationInstance {
             name: &self.application_name,
             sub_name: self.sub_name.as_deref(),
-            unique_id: NonZeroU128::new(1).unwrap(),
+            unique_id: self.unique_id,
             action_sets: self
                 .action_sets
                 .iter()
                 .map(|set| set.1.serialize())
                 .collect(),
             dynamic_action_sets: vec![],
-            binding_layouts: vec![],
+            binding_layouts: self
+                .binding_layouts
+                .iter()
+                .filter_map(|layout| {
+                    Some(serial::BindingLayout {
+                        name: layout.name.clone(),
+                        interaction_profile: instance
+                            .get_path_string(layout.interaction_profile)?,
+                        bindings: layout
+                            .bindings
+                            .iter()
+                            .filter_map(|binding| {
+                                let serial = serial::serialize_binding(&instance, binding);
+                                if serial.is_none() {
+                                    log::warn!(
+                                        "SuInput: left {binding:?} out of the manifest, its action or paths could not be named"
+                                    );
+                                }
+                                serial
+                            })
+                            .collect(),
+                    })
+                })
+                .collect(),
         }
     }
 }
+
+/// A stable id for the application, FNV-1a of its name and sub name so it survives restarts and updates
+pub(crate) fn unique_id(application_name: &str, sub_name: Option<&str>) -> NonZeroU128 {
+    const OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+    const PRIME: u128 = 0x0000000001000000000000000000013b;
+
+    let bytes = application_name.bytes().chain(
+        sub_name
+            .into_iter()
+            .flat_map(|sub_name| b"\0".iter().copied().chain(sub_name.bytes())),
+    );
+
+    let hash = bytes.fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ byte as u128).wrapping_mul(PRIME)
+    });
+
+    NonZeroU128::new(hash).unwrap_or(NonZeroU128::MIN)
+}
+
+/// Pairs each binding with its serialized form, which is what overrides are compared by
+fn keyed<'a>(
+    instance: &Instance,
+    bindings: impl Iterator<Item = &'a Binding>,
+) -> Vec<(Option<serial::Binding>, &'a Binding)> {
+    bindings
+        .map(|binding| (serial::serialize_binding(instance, binding), binding))
+        .collect()
+}
+
+/// The defaults that are missing from the saved bindings
+fn removed_bindings<K: PartialEq, T>(defaults: Vec<(K, T)>, saved: &[(K, T)]) -> Vec<T> {
+    defaults
+        .into_iter()
+        .filter(|(key, _)| !saved.iter().any(|(saved, _)| saved == key))
+        .map(|(_, binding)| binding)
+        .collect()
+}
+
+/// The override along with the defaults that were neither removed nor already part of it
+fn layer_bindings<K: PartialEq, T>(
+    defaults: Vec<(K, T)>,
+    removed: &[K],
+    overrides: Vec<(K, T)>,
+) -> Vec<T> {
+    let kept = defaults
+        .into_iter()
+        .filter(|(key, _)| {
+            !removed.contains(key) && !overrides.iter().any(|(binding, _)| binding == key)
+        })
+        .collect::<Vec<_>>();
+
+    kept.into_iter()
+        .chain(overrides)
+        .map(|(_, binding)| binding)
+        .collect()
+}
+
+/// `<interaction_profile>.removed.kdl` next to the override
+fn removed_bindings_path(file_path: &Path) -> PathBuf {
+    file_path.with_extension("removed.kdl")
+}
+
+fn remove_file(file_path: &Path) -> Result<()> {
+    match std::fs::remove_file(file_path) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(Error::IoError),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //Bindings are written as "action@input" and are their own key
+    fn keyed<'a>(bindings: &[&'a str]) -> Vec<(&'a str, &'a str)> {
+        bindings
+            .iter()
+            .map(|binding| (*binding, *binding))
+            .collect()
+    }
+
+    /// Saves an override over `defaults` and loads it after the defaults changed to `updated_defaults`
+    fn save_and_load(defaults: &[&str], saved: &[&str], updated_defaults: &[&str]) -> Vec<String> {
+        let removed = removed_bindings(keyed(defaults), &keyed(saved));
+
+        layer_bindings(keyed(updated_defaults), &removed, keyed(saved))
+            .into_iter()
+            .map(str::to_owned)
+            .collect()
+    }
+
+    #[test]
+    fn unbind_default() {
+        let defaults = ["jump@a", "crouch@b"];
+        assert_eq!(save_and_load(&defaults, &["jump@a"], &defaults), ["jump@a"]);
+    }
+
+    #[test]
+    fn move_input_to_another_action() {
+        let defaults = ["jump@a", "crouch@b"];
+        assert_eq!(
+            save_and_load(&defaults, &["crouch@b", "crouch@a"], &defaults),
+            ["crouch@b", "crouch@a"]
+        );
+    }
+
+    #[test]
+    fn keep_defaults_added_by_updates() {
+        assert_eq!(
+            save_and_load(
+                &["jump@a", "crouch@b"],
+                &["jump@x", "crouch@b"],
+                &["jump@a", "crouch@b", "sprint@c"]
+            ),
+            ["sprint@c", "jump@x", "crouch@b"]
+        );
+    }
+}
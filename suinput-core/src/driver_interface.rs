@@ -6,6 +6,9 @@ use suinput_types::{event::*, SuPath};
 
 use crate::internal::types::HashMap;
 
+pub use crate::internal::input_component::InputComponentType;
+pub use crate::internal::output_component::OutputComponentType;
+
 /**
  * The connection from a driver to the runtime
  */
@@ -24,11 +27,65 @@ impl Deref for RuntimeInterface {
 pub enum RuntimeInterfaceError {
     #[error("Driver Uninitialized")]
     DriverUninitialized,
+    #[error("Invalid Path Handle `{0:X?}`")]
+    InvalidPathHandle(SuPath),
+    #[error("Unknown Device Type `{0}`")]
+    UnknownDeviceType(String),
+    #[error("Device Type Already Registered `{0}`")]
+    DeviceTypeAlreadyRegistered(String),
+    #[error("Interaction Profile Already Registered `{0}`")]
+    InteractionProfileAlreadyRegistered(String),
+    #[error("Bad Device Type Path `{0}`")]
+    BadDeviceTypePath(String),
+    #[error("Bad Interaction Profile Path `{0}`")]
+    BadInteractionProfilePath(String),
+    #[error("Bad Component Path `{0}`")]
+    BadComponentPath(String),
+    #[error("Bad User Path `{0}`")]
+    BadUserPath(String),
+}
+
+/// A device type described by a driver rather than the runtime's built-in configs
+#[derive(Debug, Clone)]
+pub struct DeviceTypeInfo {
+    /// /devices/<vendor_name>/<type_name>
+    pub id: SuPath,
+    /// The parent's input components are inherited and can be extended
+    pub parent: Option<SuPath>,
+    pub input_components: Vec<(SuPath, InputComponentType)>,
+    pub output_components: Vec<(SuPath, OutputComponentType)>,
+}
+
+/// An interaction profile described by a driver rather than the runtime's built-in configs
+#[derive(Debug, Clone)]
+pub struct InteractionProfileInfo {
+    /// /interaction_profiles/<vendor_name>/<type_name>
+    pub id: SuPath,
+    /// (user path, device type)
+    pub user2device: Vec<(SuPath, SuPath)>,
+    /// ((user path, alias input path), device input path)
+    pub aliases: Vec<((SuPath, SuPath), SuPath)>,
 }
 
 pub trait RuntimeInterfaceTrait: Debug + Send + Sync {
-    fn register_new_device(&self, device_type: SuPath) -> Result<u64, RuntimeInterfaceError>;
+    /// `identity` should be stable across reconnects of the same physical device (serial number, HID instance path etc.)
+    /// so that it is given back the same device id
+    fn register_new_device(
+        &self,
+        device_type: SuPath,
+        identity: Option<&str>,
+    ) -> Result<u64, RuntimeInterfaceError>;
     fn disconnect_device(&self, device_id: u64) -> Result<(), RuntimeInterfaceError>;
+    /// Can be called before the driver is initialized
+    fn register_device_type(
+        &self,
+        device_type: DeviceTypeInfo,
+    ) -> Result<(), RuntimeInterfaceError>;
+    /// Can be called before the driver is initialized
+    fn register_interaction_profile(
+        &self,
+        interaction_profile: InteractionProfileInfo,
+    ) -> Result<(), RuntimeInterfaceError>;
     fn send_component_event(
         &self,
         component_event: InputEvent,
@@ -67,7 +124,9 @@ pub trait SuInputDriver: Send + Sync {
     fn initialize(&mut self);
     //Force a refresh
     fn poll(&self);
-    //TODO
-    fn get_component_state(&self, device: usize, path: SuPath);
+    /// The current state of an input component, or None if the driver doesn't track it
+    fn get_component_state(&self, device: u64, path: SuPath) -> Option<InputComponentEvent>;
+    /// Called from the runtime's worker thread for devices registered by this driver
+    fn handle_output_event(&self, event: OutputEvent);
     fn destroy(&mut self);
 }
@@ -4,17 +4,15 @@ use std::{
 };
 
 use crate::{
-    application_instance::ApplicationInstance,
-    internal::{paths::InteractionProfilePath, types::HashMap},
+    application_instance::{self, ApplicationInstance},
+    internal::{paths::InteractionProfilePath, serial, types::HashMap},
     types::app::InternalApplicationInstanceCreateInfo,
 };
 
 use once_cell::sync::OnceCell;
 use parking_lot::RwLock;
 use slotmap::{DefaultKey, SlotMap};
-use suinput_types::{
-    binding::SimpleBinding, event::PathFormatError, CreateBindingLayoutError, SuPath,
-};
+use suinput_types::{binding::Binding, event::PathFormatError, CreateBindingLayoutError, SuPath};
 
 use crate::{
     action::Action,
@@ -90,13 +88,13 @@ impl Instance {
         todo!()
     }
 
-    pub fn create_binding_layout(
+    pub fn create_binding_layout<B: Into<Binding> + Clone>(
         &self,
         name: &str,
         interaction_profile: SuPath,
-        bindings: &[SimpleBinding],
+        bindings: &[B],
     ) -> Result<Arc<BindingLayout>, CreateBindingLayoutError> {
-        let bindings = bindings.to_vec();
+        let bindings = bindings.iter().cloned().map(Into::into).collect::<Vec<_>>();
 
         ProcessedBindingLayout::new(self, interaction_profile, &bindings)
             .map(|processed| {
@@ -104,7 +102,7 @@ impl Instance {
                     name: name.into(),
                     interaction_profile,
                     processed_cache: processed,
-                    bindings: bindings.to_vec(),
+                    bindings,
                 })
             })
             .map_err(|err| {
@@ -113,6 +111,26 @@ impl Instance {
             })
     }
 
+    /// Saves the binding layout as JSON or KDL depending on the extension of `file_path`
+    pub fn save_binding_layout(
+        &self,
+        binding_layout: &BindingLayout,
+        file_path: &Path,
+    ) -> crate::types::Result<()> {
+        serial::save_binding_layout(self, binding_layout, file_path)
+    }
+
+    /// Loads a binding layout written by [`Instance::save_binding_layout`]
+    pub fn load_binding_layout(
+        &self,
+        file_path: &Path,
+    ) -> Result<Arc<BindingLayout>, CreateBindingLayoutError> {
+        serial::load_binding_layout(self, file_path).map_err(|err| {
+            log::error!("SuInput: load_binding_layout failed with {err}");
+            err
+        })
+    }
+
     pub fn create_application_instance(
         self: &Arc<Self>,
         create_info: InternalApplicationInstanceCreateInfo,
@@ -149,6 +167,10 @@ impl Instance {
                 index,
                 application_name: create_info.application.name.to_string(),
                 sub_name: create_info.sub_name.map(|s| s.to_string()),
+                unique_id: application_instance::unique_id(
+                    create_info.application.name,
+                    create_info.sub_name,
+                ),
                 action_sets,
                 actions,
                 binding_layouts: create_info.binding_layouts,
@@ -163,7 +185,7 @@ impl Instance {
 pub struct BindingLayout {
     pub name: String,
     pub interaction_profile: InteractionProfilePath,
-    pub bindings: Vec<SimpleBinding>,
+    pub bindings: Vec<Binding>,
 
     pub processed_cache: ProcessedBindingLayout,
 }
@@ -0,0 +1,526 @@
+use std::time::{Duration, Instant};
+
+use suinput_types::binding::activator::{
+    Activator, Impulse, ImpulseOverriddenBehavior, OverriddenBehavior,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pulse {
+    None,
+    ///Fired since the last tick
+    Fresh,
+    ///Released on the next tick
+    Aged,
+}
+
+///Turns the raw pressed state of a boolean binding into the output of its activator
+#[derive(Debug, Clone)]
+pub struct ActivatorState {
+    activator: Activator,
+    ///Hold activators wait this long before activating when a blocking activator shares their input
+    pub hold_delay: Option<Duration>,
+    ///QuickTap and Hold activators wait this long before firing a tap when a blocking MultiTap shares their input
+    pub tap_delay: Option<Duration>,
+
+    pressed: bool,
+    press_time: Option<Instant>,
+    release_time: Option<Instant>,
+    taps: u32,
+    ///The output state of the binding
+    active: bool,
+    pulse: Pulse,
+    ///The activator has activated during the current press
+    fired: bool,
+    ///Another activator on the same input has overridden this one for the current press
+    overridden: bool,
+    ///A tap impulse waiting on a blocking MultiTap
+    deferred: Option<Instant>,
+}
+
+#[derive(Debug, Default)]
+pub struct ActivatorOutput {
+    ///The new output state of the binding, if it changed
+    pub event: Option<bool>,
+    ///The activator fired and overrides the other activators on the same input
+    pub overriding: bool,
+}
+
+impl From<Option<bool>> for ActivatorOutput {
+    fn from(event: Option<bool>) -> Self {
+        Self {
+            event,
+            overriding: false,
+        }
+    }
+}
+
+impl ActivatorState {
+    pub fn new(activator: Activator) -> Self {
+        Self {
+            activator,
+            hold_delay: None,
+            tap_delay: None,
+            pressed: false,
+            press_time: None,
+            release_time: None,
+            taps: 0,
+            active: false,
+            pulse: Pulse::None,
+            fired: false,
+            overridden: false,
+            deferred: None,
+        }
+    }
+
+    ///The (hold, tap) delays this activator imposes on the other activators of its input
+    pub fn blocking_delays(&self) -> (Option<Duration>, Option<Duration>) {
+        match self.activator {
+            Activator::QuickTap {
+                blocking: true,
+                max_hold_duration,
+                ..
+            } => (Some(millis(max_hold_duration)), None),
+            Activator::MultiTap {
+                blocking: true,
+                duration,
+                ..
+            } => (Some(millis(duration)), Some(millis(duration))),
+            _ => (None, None),
+        }
+    }
+
+    ///Forgets the current press, used when the binding is interrupted
+    pub fn reset(&mut self) {
+        *self = Self {
+            hold_delay: self.hold_delay,
+            tap_delay: self.tap_delay,
+            ..Self::new(self.activator)
+        };
+    }
+
+    pub fn on_input(&mut self, pressed: bool, time: Instant) -> ActivatorOutput {
+        if pressed == self.pressed {
+            return ActivatorOutput::default();
+        }
+
+        self.pressed = pressed;
+
+        if pressed {
+            self.on_press(time)
+        } else {
+            self.on_release(time)
+        }
+    }
+
+    fn on_press(&mut self, time: Instant) -> ActivatorOutput {
+        let last_release = self.release_time;
+
+        self.press_time = Some(time);
+        self.fired = false;
+        self.overridden = false;
+        //Another tap means a blocking MultiTap is still in play
+        self.deferred = None;
+
+        match self.activator {
+            Activator::Hold { impulse, .. } => {
+                if self.hold_delay.is_some() {
+                    return ActivatorOutput::default();
+                }
+
+                self.fired = true;
+                self.activate(impulse).into()
+            }
+            Activator::MultiTap {
+                duration,
+                impulse,
+                taps,
+                ..
+            } => {
+                let in_time = last_release
+                    .is_some_and(|release| time.duration_since(release) <= millis(duration));
+                self.taps = if in_time { self.taps + 1 } else { 1 };
+
+                if self.taps < taps {
+                    return ActivatorOutput::default();
+                }
+
+                self.taps = 0;
+                self.fired = true;
+                ActivatorOutput {
+                    event: self.activate(impulse),
+                    overriding: true,
+                }
+            }
+            Activator::QuickTap { .. } | Activator::LongHold { .. } => ActivatorOutput::default(),
+        }
+    }
+
+    fn on_release(&mut self, time: Instant) -> ActivatorOutput {
+        let held = self
+            .press_time
+            .map_or(Duration::ZERO, |press| time.duration_since(press));
+        self.release_time = Some(time);
+
+        match self.activator {
+            Activator::Hold { impulse, .. } => {
+                let pending = !self.fired && !self.overridden;
+                //The delay ran out between ticks
+                let late = pending && self.hold_delay.is_some_and(|delay| held >= delay);
+
+                if late && impulse != Impulse::OnRelease {
+                    return self.pulse().into();
+                }
+
+                //A tap still counts once the MultiTap window closes without another tap
+                if let (true, false, Some(delay)) = (pending, late, self.tap_delay) {
+                    self.deferred = Some(time + delay);
+                    return ActivatorOutput::default();
+                }
+
+                self.fired |= late;
+                self.deactivate(impulse).into()
+            }
+            Activator::QuickTap {
+                max_hold_duration, ..
+            } => {
+                if held > millis(max_hold_duration) || self.overridden {
+                    return ActivatorOutput::default();
+                }
+
+                match self.tap_delay {
+                    Some(delay) => {
+                        self.deferred = Some(time + delay);
+                        ActivatorOutput::default()
+                    }
+                    None => ActivatorOutput {
+                        event: self.pulse(),
+                        overriding: true,
+                    },
+                }
+            }
+            Activator::MultiTap { impulse, .. } => self.deactivate(impulse).into(),
+            Activator::LongHold {
+                min_hold_duration,
+                impulse,
+            } => {
+                //The hold duration ran out between ticks
+                if !self.fired && held >= millis(min_hold_duration) {
+                    self.fired = true;
+                    return ActivatorOutput {
+                        event: self.pulse(),
+                        overriding: true,
+                    };
+                }
+
+                self.deactivate(impulse).into()
+            }
+        }
+    }
+
+    ///Releases impulses from the previous tick and fires time based activations
+    pub fn on_tick(&mut self, time: Instant) -> ActivatorOutput {
+        if self.pulse == Pulse::Aged {
+            self.pulse = Pulse::None;
+            self.active = false;
+            return Some(false).into();
+        }
+
+        let output = self.on_timer(time);
+
+        if self.pulse == Pulse::Fresh {
+            self.pulse = Pulse::Aged;
+        }
+
+        output
+    }
+
+    fn on_timer(&mut self, time: Instant) -> ActivatorOutput {
+        //How long the input has been held without the activator firing
+        let held = match self.press_time {
+            Some(press) if self.pressed && !self.fired => Some(time.duration_since(press)),
+            _ => None,
+        };
+
+        match self.activator {
+            Activator::Hold { impulse, .. } => match (held, self.hold_delay, self.deferred) {
+                (Some(held), Some(delay), _) if held >= delay && !self.overridden => {
+                    self.fired = true;
+                    self.activate(impulse).into()
+                }
+                (_, _, Some(deadline)) if time >= deadline => {
+                    self.deferred = None;
+                    self.pulse().into()
+                }
+                _ => ActivatorOutput::default(),
+            },
+            Activator::QuickTap { .. } => match self.deferred {
+                Some(deadline) if time >= deadline => {
+                    self.deferred = None;
+                    ActivatorOutput {
+                        event: self.pulse(),
+                        overriding: true,
+                    }
+                }
+                _ => ActivatorOutput::default(),
+            },
+            Activator::LongHold {
+                min_hold_duration,
+                impulse,
+            } if held.is_some_and(|held| held >= millis(min_hold_duration)) => {
+                self.fired = true;
+                ActivatorOutput {
+                    event: self.activate(impulse),
+                    overriding: true,
+                }
+            }
+            _ => ActivatorOutput::default(),
+        }
+    }
+
+    ///Called when another activator on the same input fires
+    pub fn on_overridden(&mut self) -> Option<bool> {
+        let behavior = match self.activator {
+            Activator::Hold {
+                overridden_behavior,
+                ..
+            }
+            | Activator::MultiTap {
+                overridden_behavior,
+                ..
+            } => overridden_behavior,
+            Activator::QuickTap {
+                overridden_behavior: ImpulseOverriddenBehavior::Block,
+                ..
+            } => OverriddenBehavior::Block,
+            Activator::QuickTap { .. } | Activator::LongHold { .. } => OverriddenBehavior::None,
+        };
+
+        if behavior == OverriddenBehavior::None {
+            return None;
+        }
+
+        self.overridden = true;
+        self.deferred = None;
+
+        if behavior == OverriddenBehavior::Interrupt && self.active {
+            self.active = false;
+            self.pulse = Pulse::None;
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    ///Activation at the start of a press or after a delay
+    fn activate(&mut self, impulse: Impulse) -> Option<bool> {
+        match impulse {
+            Impulse::False => {
+                //Turn a pending impulse into a held state
+                self.pulse = Pulse::None;
+                if self.active {
+                    None
+                } else {
+                    self.active = true;
+                    Some(true)
+                }
+            }
+            Impulse::OnPress => self.pulse(),
+            Impulse::OnRelease => None,
+        }
+    }
+
+    ///Deactivation at the end of a press
+    fn deactivate(&mut self, impulse: Impulse) -> Option<bool> {
+        let fired = std::mem::take(&mut self.fired);
+
+        match impulse {
+            Impulse::False if self.active && self.pulse == Pulse::None => {
+                self.active = false;
+                Some(false)
+            }
+            Impulse::OnRelease if fired && !self.overridden => self.pulse(),
+            _ => None,
+        }
+    }
+
+    ///Activates for a single tick
+    fn pulse(&mut self) -> Option<bool> {
+        if self.active {
+            return None;
+        }
+
+        self.active = true;
+        self.pulse = Pulse::Fresh;
+        Some(true)
+    }
+}
+
+fn millis(duration: u32) -> Duration {
+    Duration::from_millis(duration as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(start: Instant, millis: u64) -> Instant {
+        start + Duration::from_millis(millis)
+    }
+
+    fn hold(overridden_behavior: OverriddenBehavior) -> ActivatorState {
+        ActivatorState::new(Activator::Hold {
+            overridden_behavior,
+            impulse: Impulse::False,
+        })
+    }
+
+    #[test]
+    fn multi_tap_window_expiry() {
+        let start = Instant::now();
+        let mut activator = ActivatorState::new(Activator::MultiTap {
+            overridden_behavior: OverriddenBehavior::None,
+            blocking: false,
+            duration: 200,
+            impulse: Impulse::False,
+            taps: 2,
+        });
+
+        assert_eq!(activator.on_input(true, at(start, 0)).event, None);
+        assert_eq!(activator.on_input(false, at(start, 50)).event, None);
+        //Too late for a double tap, counts as the first tap again
+        assert_eq!(activator.on_input(true, at(start, 400)).event, None);
+        assert_eq!(activator.on_input(false, at(start, 450)).event, None);
+
+        let output = activator.on_input(true, at(start, 500));
+        assert_eq!(output.event, Some(true));
+        assert!(output.overriding);
+        assert_eq!(activator.on_input(false, at(start, 550)).event, Some(false));
+    }
+
+    #[test]
+    fn long_hold_pulses() {
+        let start = Instant::now();
+        let mut activator = ActivatorState::new(Activator::LongHold {
+            min_hold_duration: 500,
+            impulse: Impulse::OnPress,
+        });
+
+        //Fired by a tick, released on the next one
+        activator.on_input(true, at(start, 0));
+        assert_eq!(activator.on_tick(at(start, 100)).event, None);
+        let output = activator.on_tick(at(start, 600));
+        assert_eq!(output.event, Some(true));
+        assert!(output.overriding);
+        assert_eq!(activator.on_input(false, at(start, 610)).event, None);
+        assert_eq!(activator.on_tick(at(start, 616)).event, Some(false));
+
+        //Fired by a release after the duration ran out between ticks, the fresh pulse outlives the next tick
+        activator.on_input(true, at(start, 1000));
+        assert_eq!(activator.on_input(false, at(start, 1600)).event, Some(true));
+        assert_eq!(activator.on_tick(at(start, 1616)).event, None);
+        assert_eq!(activator.on_tick(at(start, 1632)).event, Some(false));
+
+        //Released too early
+        activator.on_input(true, at(start, 2000));
+        assert_eq!(activator.on_input(false, at(start, 2100)).event, None);
+        assert_eq!(activator.on_tick(at(start, 2700)).event, None);
+    }
+
+    #[test]
+    fn quick_tap_before_hold() {
+        let start = Instant::now();
+        let mut quick_tap = ActivatorState::new(Activator::QuickTap {
+            overridden_behavior: ImpulseOverriddenBehavior::None,
+            blocking: true,
+            max_hold_duration: 200,
+        });
+        let mut hold = hold(OverriddenBehavior::Block);
+        hold.hold_delay = quick_tap.blocking_delays().0;
+        assert_eq!(hold.hold_delay, Some(Duration::from_millis(200)));
+
+        //A tap fires the QuickTap and the Hold never activates
+        assert_eq!(hold.on_input(true, at(start, 0)).event, None);
+        assert_eq!(quick_tap.on_input(true, at(start, 0)).event, None);
+        assert_eq!(hold.on_tick(at(start, 100)).event, None);
+        let output = quick_tap.on_input(false, at(start, 150));
+        assert_eq!(output.event, Some(true));
+        assert!(output.overriding);
+        assert_eq!(hold.on_overridden(), None);
+        assert_eq!(hold.on_input(false, at(start, 150)).event, None);
+        assert_eq!(hold.on_tick(at(start, 400)).event, None);
+
+        //A long press activates the Hold once the delay runs out and the QuickTap stays quiet
+        hold.on_input(true, at(start, 1000));
+        quick_tap.on_input(true, at(start, 1000));
+        assert_eq!(hold.on_tick(at(start, 1100)).event, None);
+        assert_eq!(hold.on_tick(at(start, 1250)).event, Some(true));
+        assert_eq!(quick_tap.on_input(false, at(start, 1300)).event, None);
+        assert_eq!(hold.on_input(false, at(start, 1300)).event, Some(false));
+    }
+
+    #[test]
+    fn multi_tap_blocks_hold() {
+        let start = Instant::now();
+        let mut multi_tap = ActivatorState::new(Activator::MultiTap {
+            overridden_behavior: OverriddenBehavior::None,
+            blocking: true,
+            duration: 200,
+            impulse: Impulse::OnPress,
+            taps: 2,
+        });
+        let mut hold = hold(OverriddenBehavior::Block);
+        (hold.hold_delay, hold.tap_delay) = multi_tap.blocking_delays();
+
+        //A single tap fires the Hold once the tap window expires
+        assert_eq!(hold.on_input(true, at(start, 0)).event, None);
+        multi_tap.on_input(true, at(start, 0));
+        assert_eq!(hold.on_input(false, at(start, 50)).event, None);
+        multi_tap.on_input(false, at(start, 50));
+        assert_eq!(hold.on_tick(at(start, 150)).event, None);
+        assert_eq!(hold.on_tick(at(start, 260)).event, Some(true));
+        assert_eq!(hold.on_tick(at(start, 276)).event, Some(false));
+
+        //A double tap fires the MultiTap and drops the Hold tap
+        hold.on_input(true, at(start, 1000));
+        multi_tap.on_input(true, at(start, 1000));
+        assert_eq!(hold.on_input(false, at(start, 1050)).event, None);
+        multi_tap.on_input(false, at(start, 1050));
+        assert_eq!(hold.on_input(true, at(start, 1100)).event, None);
+        let output = multi_tap.on_input(true, at(start, 1100));
+        assert_eq!(output.event, Some(true));
+        assert!(output.overriding);
+        assert_eq!(hold.on_overridden(), None);
+        assert_eq!(hold.on_input(false, at(start, 1150)).event, None);
+        assert_eq!(hold.on_tick(at(start, 1500)).event, None);
+    }
+
+    #[test]
+    fn interrupt_while_pending() {
+        let start = Instant::now();
+
+        //An active Hold is released when interrupted
+        let mut active = hold(OverriddenBehavior::Interrupt);
+        assert_eq!(active.on_input(true, at(start, 0)).event, Some(true));
+        assert_eq!(active.on_overridden(), Some(false));
+        assert_eq!(active.on_input(false, at(start, 100)).event, None);
+
+        //A delayed Hold never activates once reset
+        let mut delayed = hold(OverriddenBehavior::None);
+        delayed.hold_delay = Some(Duration::from_millis(200));
+        delayed.on_input(true, at(start, 0));
+        delayed.reset();
+        assert_eq!(delayed.on_tick(at(start, 300)).event, None);
+
+        //A deferred QuickTap is dropped when overridden
+        let mut quick_tap = ActivatorState::new(Activator::QuickTap {
+            overridden_behavior: ImpulseOverriddenBehavior::Block,
+            blocking: false,
+            max_hold_duration: 200,
+        });
+        quick_tap.tap_delay = Some(Duration::from_millis(200));
+        quick_tap.on_input(true, at(start, 0));
+        assert_eq!(quick_tap.on_input(false, at(start, 100)).event, None);
+        assert_eq!(quick_tap.on_overridden(), None);
+        assert_eq!(quick_tap.on_tick(at(start, 400)).event, None);
+    }
+}
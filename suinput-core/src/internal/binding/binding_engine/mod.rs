@@ -1,7 +1,10 @@
 use std::{cell::RefCell, sync::Arc};
 
 use hashbrown::HashSet;
-use suinput_types::action::{ActionListener, ActionStateEnum};
+use suinput_types::{
+    action::{ActionListener, ActionStateEnum},
+    WindowHandle,
+};
 
 use crate::{
     action::Action,
@@ -12,6 +15,7 @@ use crate::{
 
 use super::working_user::{AttachedBindingLayout, WorkingActionState, WorkingUser};
 
+mod activator;
 mod processed_binding;
 pub mod processed_binding_layout;
 
@@ -26,6 +30,7 @@ pub struct WorkingUserInterface<'a> {
     pub(crate) compound_action_states: &'a mut HashMap<u64, Box<dyn CompoundActionState>>,
     pub(crate) callbacks: &'a mut [Box<dyn ActionListener>],
     pub(crate) actions: &'a HashMap<u64, Arc<Action>>,
+    pub(crate) window: Option<WindowHandle>,
 }
 
 impl<'a> WorkingUserInterface<'a> {
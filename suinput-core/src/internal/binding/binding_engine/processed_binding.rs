@@ -1,12 +1,27 @@
-use std::time::Instant;
+use std::{
+    collections::VecDeque,
+    f32::consts::{FRAC_PI_2, FRAC_PI_4, PI, TAU},
+    time::{Duration, Instant},
+};
 
 use nalgebra::{UnitVector3, Vector2, Vector3};
 use suinput_types::{
-    action::ActionStateEnum,
-    event::{InputComponentEvent, InputEvent},
-    SuPath,
+    action::{ActionStateEnum, Pose},
+    binding::{
+        chord::ChordType,
+        gyro::{GyroFiltering, GyroSpace, XAxis},
+        joystick::{
+            DeadzoneShape, EdgeAcceleration, JoystickProcessing, ResponseCurve, Sectors, StickAxis,
+            StickRegion,
+        },
+        touchpad::TouchpadRegion,
+        Sensitivity,
+    },
+    event::{Cursor, InputComponentEvent, InputEvent, TouchpadState},
+    SuPath, WindowHandle,
 };
 
+use super::activator::ActivatorState;
 use crate::internal::{
     device::DeviceState,
     input_component::{InputComponentData, InputComponentState, InternalActionState},
@@ -22,9 +37,114 @@ pub struct ProcessedInputBinding {
     pub state: InternalActionState,
     pub action: u64,
     pub input_component: (UserPath, InputPath),
+    pub activator: Option<ActivatorState>,
+    pub chords: Vec<ChordState>,
+    /// False while the chords gate the binding or a more specific chord on the same input shadows it
+    pub chord_open: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChordState {
+    pub modifier: (UserPath, InputPath),
+    pub ty: ChordType,
+    pub threshold: f32,
+    pub held: bool,
+    pub toggled: bool,
+}
+
+impl ChordState {
+    pub fn new(modifier: (UserPath, InputPath), ty: ChordType, threshold: f32) -> Self {
+        Self {
+            modifier,
+            ty,
+            threshold,
+            held: false,
+            toggled: false,
+        }
+    }
+
+    pub fn on_modifier(&mut self, held: bool) {
+        if self.ty == ChordType::Toggle && held && !self.held {
+            self.toggled = !self.toggled;
+        }
+        self.held = held;
+    }
+
+    pub fn is_open(&self) -> bool {
+        match self.ty {
+            ChordType::PressToEnable => self.held,
+            ChordType::PressToDisable => !self.held,
+            ChordType::Toggle => self.toggled,
+        }
+    }
+
+    /// Returns None if the event can't drive a modifier
+    pub fn is_held(&self, event: &InputComponentEvent) -> Option<bool> {
+        match event {
+            InputComponentEvent::Button(state) => Some(*state),
+            InputComponentEvent::Trigger(state) => Some(*state > self.threshold),
+            _ => None,
+        }
+    }
+
+    /// Like [`ChordState::is_held`] for the cached state of the modifier
+    pub fn is_held_state(&self, state: &InputComponentState) -> Option<bool> {
+        match state {
+            InputComponentState::Button(state) => Some(*state),
+            InputComponentState::Trigger(state) => Some(*state > self.threshold),
+            _ => None,
+        }
+    }
 }
 
 impl ProcessedInputBinding {
+    /// Returns None if a chord is gating the binding, otherwise the number of held PressToEnable and open Toggle chords
+    pub fn chord_specificity(&self) -> Option<usize> {
+        self.chords.iter().all(ChordState::is_open).then(|| {
+            self.chords
+                .iter()
+                .filter(|chord| chord.ty != ChordType::PressToDisable)
+                .count()
+        })
+    }
+
+    /// Passes boolean binding events through the activator, returning the output event and whether it overrides the other activators of the input
+    pub fn apply_activator(
+        &mut self,
+        event: ActionStateEnum,
+        time: Instant,
+    ) -> (Option<ActionStateEnum>, bool) {
+        match (&mut self.activator, event) {
+            (Some(activator), ActionStateEnum::Boolean(pressed)) => {
+                let output = activator.on_input(pressed, time);
+                (
+                    output.event.map(ActionStateEnum::Boolean),
+                    output.overriding,
+                )
+            }
+            (_, event) => (Some(event), false),
+        }
+    }
+
+    /// Activates the binding from a cached component state unless a chord is gating it
+    pub fn activate(&mut self, data: InputComponentData, time: Instant) -> Option<ActionStateEnum> {
+        if !self.chord_open {
+            return None;
+        }
+
+        self.ty
+            .activate(data)
+            .and_then(|event| self.apply_activator(event, time).0)
+    }
+
+    pub fn interrupt(&mut self) -> Option<ActionStateEnum> {
+        if let Some(activator) = &mut self.activator {
+            activator.reset();
+        }
+
+        self.ty.interrupt(&self.state)
+    }
+
     pub fn save_state(&mut self, action_state: &ActionStateEnum) {
         match action_state {
             ActionStateEnum::Boolean(state) => self.state = InternalActionState::Boolean(*state),
@@ -42,21 +162,82 @@ impl ProcessedInputBinding {
 pub enum ProcessedBindingType {
     Button2Bool,
     Button2Value,
+    Button2Axis1d,
     Move2d2Delta2d {
         sensitivity: (f64, f64),
     },
-    Trigger2Bool,
+    Cursor2Cursor,
+    Trigger2Bool {
+        press_threshold: f32,
+        release_threshold: f32,
+        pressed: bool,
+    },
     Trigger2Value,
-    Joystick2Axis2d,
+    Trigger2Axis1d,
+    Joystick2Axis2d {
+        processing: JoystickProcessing,
+    },
+    Joystick2Axis1d {
+        axis: StickAxis,
+        processing: JoystickProcessing,
+        invert: bool,
+    },
+    Joystick2Bool {
+        region: StickRegion,
+        pressed: bool,
+    },
+    /// Flick stick, the output is the camera yaw in degrees scaled by the sensitivity
+    Joystick2Delta2d {
+        flick_time: Duration,
+        threshold: f32,
+        sensitivity: f64,
+        /// The stick angle while it is past the threshold
+        last_angle: Option<f32>,
+        flick: Option<Flick>,
+    },
+    Joystick2CameraDelta2d {
+        processing: JoystickProcessing,
+        sensitivity: Sensitivity<f32>,
+        edge_acceleration: Option<EdgeAcceleration>,
+        /// The processed stick position
+        position: Vector2<f32>,
+        last_time: Option<Instant>,
+        /// When the stick reached the edge
+        edge_time: Option<Instant>,
+    },
+    Touchpad2Bool {
+        region: TouchpadRegion,
+        finger: Option<usize>,
+        pressed: bool,
+    },
+    Touchpad2Axis2d {
+        region: TouchpadRegion,
+        finger: Option<usize>,
+    },
+    /// The first finger in the region points at the same spot on the session's window, the region is stretched over the whole window
+    Touchpad2Cursor {
+        region: TouchpadRegion,
+        finger: Option<usize>,
+    },
+    Touchpad2Delta2d {
+        region: TouchpadRegion,
+        finger: Option<usize>,
+        /// In region widths and heights
+        sensitivity: (f64, f64),
+        /// The finger being tracked and its last position within the region
+        tracked: Option<(usize, Vector2<f32>)>,
+    },
     Gyro2Delta2d {
         last_time: Option<Instant>,
         space: GyroBindingSpace,
-        // cut_off_speed: f32,
-        // cut_off_recovery: f32,
-        // smooth_threshold: f32,
-        // smooth_time: f32,
+        filtering: GyroFiltering,
+        /// Recent angular velocities within the smoothing window
+        smoothing_buffer: VecDeque<(Instant, Vector2<f32>)>,
         sensitivity: Sensitivity<f32>,
+        invert: (bool, bool),
     },
+    /// The orientation of the device's motion sensors
+    Gyro2Pose,
 }
 
 impl ProcessedBindingType {
@@ -67,6 +248,7 @@ impl ProcessedBindingType {
         event: &InputEvent,
         interaction_profile: &InteractionProfileState,
         devices: &ParallelArena<(DeviceState, InteractionProfilePath)>,
+        window: Option<WindowHandle>,
     ) -> Option<ActionStateEnum> {
         match (self, event.data) {
             (ProcessedBindingType::Button2Bool, InputComponentEvent::Button(state)) => {
@@ -75,12 +257,36 @@ impl ProcessedBindingType {
             (ProcessedBindingType::Button2Value, InputComponentEvent::Button(state)) => {
                 Some(ActionStateEnum::Value(if state { 1.0 } else { 0.0 }))
             }
-            (ProcessedBindingType::Trigger2Bool, InputComponentEvent::Trigger(state)) => {
-                Some(ActionStateEnum::Boolean(state > 0.5))
+            (ProcessedBindingType::Button2Axis1d, InputComponentEvent::Button(state)) => {
+                Some(ActionStateEnum::Axis1d(if state { 1.0 } else { 0.0 }))
+            }
+            (
+                ProcessedBindingType::Trigger2Bool {
+                    press_threshold,
+                    release_threshold,
+                    pressed,
+                },
+                InputComponentEvent::Trigger(state),
+            ) => {
+                let new_pressed = if *pressed {
+                    state > *release_threshold
+                } else {
+                    state > *press_threshold
+                };
+
+                if new_pressed != *pressed {
+                    *pressed = new_pressed;
+                    Some(ActionStateEnum::Boolean(new_pressed))
+                } else {
+                    None
+                }
             }
             (ProcessedBindingType::Trigger2Value, InputComponentEvent::Trigger(state)) => {
                 Some(ActionStateEnum::Value(state))
             }
+            (ProcessedBindingType::Trigger2Axis1d, InputComponentEvent::Trigger(state)) => {
+                Some(ActionStateEnum::Axis1d(state))
+            }
             (
                 ProcessedBindingType::Move2d2Delta2d { sensitivity },
                 InputComponentEvent::Move2D(delta),
@@ -88,14 +294,203 @@ impl ProcessedBindingType {
                 x: delta.x * sensitivity.0,
                 y: delta.y * sensitivity.1,
             })),
-            (ProcessedBindingType::Joystick2Axis2d, InputComponentEvent::Joystick(state)) => {
-                Some(ActionStateEnum::Axis2d(state))
+            (
+                ProcessedBindingType::Joystick2Axis2d { processing },
+                InputComponentEvent::Joystick(state),
+            ) => Some(ActionStateEnum::Axis2d(
+                process_joystick(processing, state.into()).into(),
+            )),
+            (
+                ProcessedBindingType::Joystick2Axis1d {
+                    axis,
+                    processing,
+                    invert,
+                },
+                InputComponentEvent::Joystick(state),
+            ) => Some(ActionStateEnum::Axis1d(stick_axis(
+                *axis,
+                processing,
+                *invert,
+                state.into(),
+            ))),
+            (
+                ProcessedBindingType::Joystick2Bool { region, pressed },
+                InputComponentEvent::Joystick(state),
+            ) => {
+                let new_pressed = stick_region_contains(region, state.into());
+                if new_pressed != *pressed {
+                    *pressed = new_pressed;
+                    Some(ActionStateEnum::Boolean(new_pressed))
+                } else {
+                    None
+                }
+            }
+            (
+                ProcessedBindingType::Joystick2Delta2d {
+                    flick_time,
+                    threshold,
+                    sensitivity,
+                    last_angle,
+                    flick,
+                },
+                InputComponentEvent::Joystick(state),
+            ) => {
+                let now = Instant::now();
+                let stick = Vector2::from(state);
+
+                let turn = if stick.magnitude() < *threshold {
+                    *last_angle = None;
+                    0.
+                } else {
+                    //0 is forward and turning right is positive
+                    let angle = stick.x.atan2(stick.y);
+
+                    match last_angle.replace(angle) {
+                        //Rotating the stick while it is held turns by the same angle
+                        Some(last_angle) => wrap_angle(angle - last_angle),
+                        //Pushing the stick past the threshold flicks to its direction
+                        None => {
+                            *flick = Some(Flick {
+                                start: now,
+                                angle,
+                                progress: 0.,
+                            });
+                            0.
+                        }
+                    }
+                };
+
+                let turn = turn + advance_flick(flick, *flick_time, now);
+
+                if turn == 0. {
+                    return None;
+                }
+
+                Some(ActionStateEnum::Delta2d(mint::Vector2 {
+                    x: (turn.to_degrees() as f64) * *sensitivity,
+                    y: 0.,
+                }))
+            }
+            (
+                ProcessedBindingType::Joystick2CameraDelta2d {
+                    processing,
+                    sensitivity,
+                    edge_acceleration,
+                    position,
+                    last_time,
+                    edge_time,
+                },
+                InputComponentEvent::Joystick(state),
+            ) => {
+                let now = Instant::now();
+
+                //The stick was at the old position until now
+                let turn = advance_camera_stick(
+                    sensitivity,
+                    edge_acceleration,
+                    *position,
+                    last_time,
+                    *edge_time,
+                    now,
+                );
+
+                *position = process_joystick(processing, state.into());
+                *last_time = Some(now);
+                *edge_time = match edge_acceleration {
+                    Some(edge_acceleration)
+                        if position.magnitude() >= edge_acceleration.threshold =>
+                    {
+                        edge_time.or(Some(now))
+                    }
+                    _ => None,
+                };
+
+                turn
+            }
+            (
+                ProcessedBindingType::Touchpad2Bool {
+                    region,
+                    finger,
+                    pressed,
+                },
+                InputComponentEvent::Touchpad(state),
+            ) => {
+                let new_pressed = touchpad_points(&state, region, *finger).next().is_some();
+                if new_pressed != *pressed {
+                    *pressed = new_pressed;
+                    Some(ActionStateEnum::Boolean(new_pressed))
+                } else {
+                    None
+                }
+            }
+            (
+                ProcessedBindingType::Touchpad2Axis2d { region, finger },
+                InputComponentEvent::Touchpad(state),
+            ) => Some(ActionStateEnum::Axis2d(
+                touchpad_stick(&state, region, *finger).into(),
+            )),
+            (ProcessedBindingType::Cursor2Cursor, InputComponentEvent::Cursor(cursor)) => {
+                //Cursors over other windows are ignored
+                if Some(cursor.window) == window {
+                    Some(ActionStateEnum::Cursor(Some(cursor)))
+                } else {
+                    None
+                }
+            }
+            (
+                ProcessedBindingType::Touchpad2Cursor { region, finger },
+                InputComponentEvent::Touchpad(state),
+            ) => {
+                let (_, position) = touchpad_points(&state, region, *finger).next()?;
+                Some(ActionStateEnum::Cursor(Some(Cursor {
+                    normalized_window_coords: mint::Vector2 {
+                        x: position.x as f64,
+                        y: position.y as f64,
+                    },
+                    window: window?,
+                })))
+            }
+            (
+                ProcessedBindingType::Touchpad2Delta2d {
+                    region,
+                    finger,
+                    sensitivity,
+                    tracked,
+                },
+                InputComponentEvent::Touchpad(state),
+            ) => {
+                //Keep following the same finger until it lifts, even outside the region, so a second touch doesn't make the cursor jump
+                let still_down = tracked.and_then(|(idx, last_position)| {
+                    let point = &state.points[idx];
+                    point.down.then(|| {
+                        let (x, y) = region.normalize(point.position.x, point.position.y);
+                        (idx, last_position, Vector2::new(x, y))
+                    })
+                });
+
+                match still_down {
+                    Some((idx, last_position, position)) => {
+                        *tracked = Some((idx, position));
+                        let delta = position - last_position;
+                        Some(ActionStateEnum::Delta2d(mint::Vector2 {
+                            x: delta.x as f64 * sensitivity.0,
+                            y: delta.y as f64 * sensitivity.1,
+                        }))
+                    }
+                    None => {
+                        *tracked = touchpad_points(&state, region, *finger).next();
+                        None
+                    }
+                }
             }
             (
                 ProcessedBindingType::Gyro2Delta2d {
                     last_time,
                     space,
+                    filtering,
+                    smoothing_buffer,
                     sensitivity,
+                    invert,
                 },
                 InputComponentEvent::Gyro(_),
             ) => {
@@ -108,40 +503,77 @@ impl ProcessedBindingType {
                     let angular_velocity = motion.get_calibrated_gyro();
 
                     let delta = space.transform(angular_velocity, motion.get_gravity());
+                    let delta = filter_gyro(filtering, smoothing_buffer, delta, now);
 
                     if delta.x.abs() < 0.001 && delta.y.abs() < 0.001 {
                         return None;
                     }
 
-                    let sensitivity = match *sensitivity {
-                        Sensitivity::Linear(sensitivity) => sensitivity,
-                        Sensitivity::Acceleration {
-                            slow_threshold,
-                            fast_threshold,
-                            slow_scale,
-                            fast_scale,
-                        } => {
-                            let speed = delta.magnitude();
-                            let slow_fast_factor =
-                                inv_lerp(slow_threshold, fast_threshold, speed).clamp(0., 1.);
-                            lerp(slow_scale, fast_scale, slow_fast_factor)
-                        }
-                    };
+                    let sensitivity = sensitivity_scale(sensitivity, delta.magnitude());
+
+                    let sign = |inverted: bool| if inverted { -1. } else { 1. };
 
                     //TODO investigate turning sign relation
                     Some(ActionStateEnum::Delta2d(mint::Vector2 {
-                        x: (-delta.x * delta_time * sensitivity) as f64,
-                        y: (delta.y * delta_time * sensitivity) as f64,
+                        x: (-delta.x * delta_time * sensitivity * sign(invert.0)) as f64,
+                        y: (delta.y * delta_time * sensitivity * sign(invert.1)) as f64,
                     }))
                 } else {
                     *last_time = Some(Instant::now());
                     None
                 }
             }
+            (ProcessedBindingType::Gyro2Pose, InputComponentEvent::Gyro(_)) => {
+                let motion = interaction_profile.get_motion(user_path, devices).ok()?;
+
+                Some(ActionStateEnum::Pose(Pose {
+                    orientation: motion.get_orientation().into_inner().into(),
+                    position: None,
+                }))
+            }
             _ => todo!(),
         }
     }
 
+    /// Called once per sync for bindings whose output depends on time instead of events
+    pub(crate) fn on_tick(&mut self, now: Instant) -> Option<ActionStateEnum> {
+        match self {
+            ProcessedBindingType::Joystick2Delta2d {
+                flick_time,
+                sensitivity,
+                flick,
+                ..
+            } => {
+                let turn = advance_flick(flick, *flick_time, now);
+
+                if turn == 0. {
+                    return None;
+                }
+
+                Some(ActionStateEnum::Delta2d(mint::Vector2 {
+                    x: (turn.to_degrees() as f64) * *sensitivity,
+                    y: 0.,
+                }))
+            }
+            ProcessedBindingType::Joystick2CameraDelta2d {
+                sensitivity,
+                edge_acceleration,
+                position,
+                last_time,
+                edge_time,
+                ..
+            } => advance_camera_stick(
+                sensitivity,
+                edge_acceleration,
+                *position,
+                last_time,
+                *edge_time,
+                now,
+            ),
+            _ => None,
+        }
+    }
+
     pub(crate) fn activate(&mut self, data: InputComponentData) -> Option<ActionStateEnum> {
         match (self, data.state) {
             (ProcessedBindingType::Button2Bool, InputComponentState::Button(state)) => {
@@ -158,8 +590,34 @@ impl ProcessedBindingType {
                     None
                 }
             }
-            (ProcessedBindingType::Trigger2Bool, InputComponentState::Trigger(state)) => {
-                if state > 0.5 {
+            (ProcessedBindingType::Button2Axis1d, InputComponentState::Button(state)) => {
+                if state {
+                    Some(ActionStateEnum::Axis1d(1.0))
+                } else {
+                    None
+                }
+            }
+            (
+                ProcessedBindingType::Trigger2Bool {
+                    press_threshold,
+                    pressed,
+                    ..
+                },
+                InputComponentState::Trigger(state),
+            ) => {
+                if state > *press_threshold {
+                    *pressed = true;
+                    Some(ActionStateEnum::Boolean(true))
+                } else {
+                    None
+                }
+            }
+            (
+                ProcessedBindingType::Joystick2Bool { region, pressed },
+                InputComponentState::Joystick(state),
+            ) => {
+                if stick_region_contains(region, state) {
+                    *pressed = true;
                     Some(ActionStateEnum::Boolean(true))
                 } else {
                     None
@@ -172,13 +630,85 @@ impl ProcessedBindingType {
                     None
                 }
             }
-            (ProcessedBindingType::Joystick2Axis2d, InputComponentState::Joystick(state)) => {
+            (ProcessedBindingType::Trigger2Axis1d, InputComponentState::Trigger(state)) => {
+                if state != 0. {
+                    Some(ActionStateEnum::Axis1d(state))
+                } else {
+                    None
+                }
+            }
+            (
+                ProcessedBindingType::Joystick2Axis1d {
+                    axis,
+                    processing,
+                    invert,
+                },
+                InputComponentState::Joystick(state),
+            ) => {
+                let state = stick_axis(*axis, processing, *invert, state);
+                if state != 0. {
+                    Some(ActionStateEnum::Axis1d(state))
+                } else {
+                    None
+                }
+            }
+            (
+                ProcessedBindingType::Joystick2Axis2d { processing },
+                InputComponentState::Joystick(state),
+            ) => {
+                let state = process_joystick(processing, state);
                 if state.magnitude_squared() != 0. {
                     Some(ActionStateEnum::Axis2d(state.into()))
                 } else {
                     None
                 }
             }
+            (
+                ProcessedBindingType::Touchpad2Bool {
+                    region,
+                    finger,
+                    pressed,
+                },
+                InputComponentState::Touchpad(state),
+            ) => {
+                if touchpad_points(&state, region, *finger).next().is_some() {
+                    *pressed = true;
+                    Some(ActionStateEnum::Boolean(true))
+                } else {
+                    None
+                }
+            }
+            (
+                ProcessedBindingType::Joystick2CameraDelta2d {
+                    processing,
+                    edge_acceleration,
+                    position,
+                    last_time,
+                    edge_time,
+                    ..
+                },
+                InputComponentState::Joystick(state),
+            ) => {
+                //Turning starts on the next tick
+                let now = Instant::now();
+                *position = process_joystick(processing, state);
+                *last_time = Some(now);
+                *edge_time = edge_acceleration
+                    .filter(|edge_acceleration| position.magnitude() >= edge_acceleration.threshold)
+                    .map(|_| now);
+                None
+            }
+            (
+                ProcessedBindingType::Touchpad2Axis2d { region, finger },
+                InputComponentState::Touchpad(state),
+            ) => {
+                let stick = touchpad_stick(&state, region, *finger);
+                if stick.magnitude_squared() != 0. {
+                    Some(ActionStateEnum::Axis2d(stick.into()))
+                } else {
+                    None
+                }
+            }
             _ => None,
         }
     }
@@ -187,6 +717,38 @@ impl ProcessedBindingType {
         &mut self,
         binding_state: &InternalActionState,
     ) -> Option<ActionStateEnum> {
+        match self {
+            ProcessedBindingType::Touchpad2Bool { pressed, .. }
+            | ProcessedBindingType::Trigger2Bool { pressed, .. }
+            | ProcessedBindingType::Joystick2Bool { pressed, .. } => *pressed = false,
+            ProcessedBindingType::Touchpad2Delta2d { tracked, .. } => *tracked = None,
+            ProcessedBindingType::Joystick2Delta2d {
+                last_angle, flick, ..
+            } => {
+                *last_angle = None;
+                *flick = None;
+            }
+            ProcessedBindingType::Joystick2CameraDelta2d {
+                position,
+                last_time,
+                edge_time,
+                ..
+            } => {
+                *position = Vector2::zeros();
+                *last_time = None;
+                *edge_time = None;
+            }
+            ProcessedBindingType::Gyro2Delta2d {
+                last_time,
+                smoothing_buffer,
+                ..
+            } => {
+                *last_time = None;
+                smoothing_buffer.clear();
+            }
+            _ => (),
+        }
+
         Some(match binding_state {
             InternalActionState::Boolean(state) => {
                 if *state {
@@ -221,39 +783,137 @@ impl ProcessedBindingType {
     }
 }
 
+/// Returns the camera turn for the time the stick has spent at its position since the last update
+fn advance_camera_stick(
+    sensitivity: &Sensitivity<f32>,
+    edge_acceleration: &Option<EdgeAcceleration>,
+    position: Vector2<f32>,
+    last_time: &mut Option<Instant>,
+    edge_time: Option<Instant>,
+    now: Instant,
+) -> Option<ActionStateEnum> {
+    let delta_time = now.duration_since(last_time.replace(now)?).as_secs_f32();
+
+    if position.magnitude_squared() == 0. || delta_time == 0. {
+        return None;
+    }
+
+    let multiplier = match (edge_acceleration, edge_time) {
+        (Some(edge_acceleration), Some(edge_time)) => {
+            let ramp = if edge_acceleration.ramp_time > 0. {
+                (now.duration_since(edge_time).as_secs_f32() / edge_acceleration.ramp_time).min(1.)
+            } else {
+                1.
+            };
+            lerp(1., edge_acceleration.multiplier, ramp)
+        }
+        _ => 1.,
+    };
+
+    let turn =
+        position * sensitivity_scale(sensitivity, position.magnitude()) * multiplier * delta_time;
+
+    Some(ActionStateEnum::Delta2d(mint::Vector2 {
+        x: turn.x as f64,
+        y: -turn.y as f64,
+    }))
+}
+
+fn sensitivity_scale(sensitivity: &Sensitivity<f32>, speed: f32) -> f32 {
+    match *sensitivity {
+        Sensitivity::Linear(sensitivity) => sensitivity,
+        Sensitivity::Acceleration {
+            slow_threshold,
+            fast_threshold,
+            slow_scale,
+            fast_scale,
+        } => {
+            let slow_fast_factor = inv_lerp(slow_threshold, fast_threshold, speed).clamp(0., 1.);
+            lerp(slow_scale, fast_scale, slow_fast_factor)
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
-pub enum Sensitivity<T> {
-    Linear(T),
-    Acceleration {
-        slow_threshold: T,
-        fast_threshold: T,
-        slow_scale: T,
-        fast_scale: T,
-    },
+pub struct Flick {
+    start: Instant,
+    /// The total angle to turn in radians
+    angle: f32,
+    /// How much of the angle has already been output, from 0 to 1
+    progress: f32,
+}
+
+/// Returns the angle turned by the flick since it was last advanced
+fn advance_flick(flick: &mut Option<Flick>, flick_time: Duration, now: Instant) -> f32 {
+    let Some(current) = flick else {
+        return 0.;
+    };
+
+    let time = if flick_time.is_zero() {
+        1.
+    } else {
+        (now.duration_since(current.start).as_secs_f32() / flick_time.as_secs_f32()).min(1.)
+    };
+    //Ease out so the flick settles on the target direction
+    let progress = 1. - (1. - time).powi(2);
+
+    let turn = current.angle * (progress - current.progress);
+    current.progress = progress;
+
+    if time >= 1. {
+        *flick = None;
+    }
+
+    turn
+}
+
+/// Wraps an angle in radians to -PI..PI
+fn wrap_angle(angle: f32) -> f32 {
+    (angle + PI).rem_euclid(TAU) - PI
 }
 
 //http://gyrowiki.jibbsmart.com/blog:player-space-gyro-and-alternatives-explained
 #[derive(Debug, Clone, Copy)]
 pub enum GyroBindingSpace {
     LocalSpace {
-        x_axis: Axis,
+        x_axis: XAxis,
+    },
+    LocalCombinedYawRoll {
+        yaw_factor: f32,
+        roll_factor: f32,
     },
-    LocalCombinedYawRoll,
     PlayerSpace {
         //default 60° (2) for Yaw
         //default 45° (1.41) for Roll
         relax_factor: f32,
-        x_axis: Axis,
+        x_axis: XAxis,
     },
     WorldSpace {
-        x_axis: Axis,
+        x_axis: XAxis,
     },
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub enum Axis {
-    Yaw,
-    Roll,
+impl From<GyroSpace> for GyroBindingSpace {
+    fn from(space: GyroSpace) -> Self {
+        match space {
+            GyroSpace::LocalSpace { x_axis } => GyroBindingSpace::LocalSpace { x_axis },
+            GyroSpace::LocalCombinedYawRoll {
+                yaw_factor,
+                roll_factor,
+            } => GyroBindingSpace::LocalCombinedYawRoll {
+                yaw_factor,
+                roll_factor,
+            },
+            GyroSpace::PlayerSpace {
+                relax_angle,
+                x_axis,
+            } => GyroBindingSpace::PlayerSpace {
+                relax_factor: GyroBindingSpace::calc_relax_factor(relax_angle),
+                x_axis,
+            },
+            GyroSpace::WorldSpace { x_axis } => GyroBindingSpace::WorldSpace { x_axis },
+        }
+    }
 }
 
 impl GyroBindingSpace {
@@ -265,11 +925,17 @@ impl GyroBindingSpace {
     fn transform(&self, angular_velocity: Vector3<f32>, grav: Vector3<f32>) -> Vector2<f32> {
         match self {
             GyroBindingSpace::LocalSpace { x_axis } => match x_axis {
-                Axis::Yaw => Vector2::new(angular_velocity.y, angular_velocity.x),
-                Axis::Roll => Vector2::new(angular_velocity.z, angular_velocity.x),
+                XAxis::Yaw => Vector2::new(angular_velocity.y, angular_velocity.x),
+                XAxis::Roll => Vector2::new(angular_velocity.z, angular_velocity.x),
             },
-            GyroBindingSpace::LocalCombinedYawRoll => {
-                let yaw_axis = Vector2::new(angular_velocity.y, angular_velocity.z);
+            GyroBindingSpace::LocalCombinedYawRoll {
+                yaw_factor,
+                roll_factor,
+            } => {
+                let yaw_axis = Vector2::new(
+                    angular_velocity.y * yaw_factor,
+                    angular_velocity.z * roll_factor,
+                );
                 let yaw_dir = if yaw_axis.x.abs() > yaw_axis.y.abs() {
                     yaw_axis.x.signum()
                 } else {
@@ -284,7 +950,7 @@ impl GyroBindingSpace {
                 let grav = UnitVector3::new_normalize(grav);
                 let mut x = 0.;
                 match x_axis {
-                    Axis::Yaw => {
+                    XAxis::Yaw => {
                         let world_yaw = angular_velocity.y * grav.y + angular_velocity.z * grav.z;
 
                         x = world_yaw.signum()
@@ -293,7 +959,7 @@ impl GyroBindingSpace {
                                 Vector2::new(angular_velocity.y, angular_velocity.z).magnitude(),
                             );
                     }
-                    Axis::Roll => {
+                    XAxis::Roll => {
                         // project pitch axis onto gravity plane
                         let grav_dot_pitch_axis = grav.x; // shortcut for (1, 0, 0).Dot(gravNorm)
                         let pitch_vector = Vector3::new(
@@ -331,11 +997,254 @@ impl GyroBindingSpace {
                 };
                 Vector2::new(-x, angular_velocity.x)
             }
-            GyroBindingSpace::WorldSpace { x_axis: _ } => todo!(),
+            GyroBindingSpace::WorldSpace { x_axis } => {
+                let grav = UnitVector3::new_normalize(grav);
+
+                // project pitch axis onto gravity plane
+                let grav_dot_pitch_axis = grav.x;
+                let pitch_vector = Vector3::new(
+                    1. - grav.x * grav_dot_pitch_axis,
+                    0. - grav.y * grav_dot_pitch_axis,
+                    0. - grav.z * grav_dot_pitch_axis,
+                );
+
+                // the pitch axis is parallel to gravity when the controller points straight up or down
+                if pitch_vector.magnitude_squared() == 0. {
+                    return Vector2::zeros();
+                }
+                let pitch_vector = UnitVector3::new_normalize(pitch_vector);
+                let world_pitch = angular_velocity.dot(&pitch_vector);
+
+                let x = match x_axis {
+                    XAxis::Yaw => angular_velocity.dot(&grav),
+                    XAxis::Roll => {
+                        let roll_vector = UnitVector3::new_normalize(pitch_vector.cross(&grav));
+                        angular_velocity.dot(&roll_vector)
+                    }
+                };
+
+                Vector2::new(-x, world_pitch)
+            }
         }
     }
 }
 
+/// The deadzones have to leave some travel, power curves need a positive exponent and piecewise points have to be sorted within 0..1
+pub(crate) fn is_valid_joystick_processing(processing: &JoystickProcessing) -> bool {
+    let valid_curve = match &processing.curve {
+        ResponseCurve::Linear => true,
+        ResponseCurve::Power(exponent) => exponent.is_finite() && *exponent > 0.,
+        ResponseCurve::Piecewise(points) => {
+            points
+                .iter()
+                .all(|(input, output)| (0. ..=1.).contains(input) && (0. ..=1.).contains(output))
+                && points.windows(2).all(|pair| pair[0].0 <= pair[1].0)
+        }
+    };
+
+    processing.inner_deadzone >= 0.
+        && processing.outer_deadzone >= 0.
+        && processing.inner_deadzone + processing.outer_deadzone < 1.
+        && (0. ..1.).contains(&processing.anti_deadzone)
+        && valid_curve
+}
+
+/// Applies the deadzones, response curve and anti-deadzone to a joystick position
+fn process_joystick(processing: &JoystickProcessing, position: Vector2<f32>) -> Vector2<f32> {
+    let inner = processing.inner_deadzone;
+    let outer = processing.outer_deadzone;
+
+    //Maps a distance from the center past the deadzones onto the output range
+    let scale = |distance: f32| {
+        if distance <= inner {
+            return 0.;
+        }
+
+        let scaled = ((distance - inner) / (1. - inner - outer)).clamp(0., 1.);
+        let curved = match &processing.curve {
+            ResponseCurve::Linear => scaled,
+            ResponseCurve::Power(exponent) => scaled.powf(*exponent),
+            ResponseCurve::Piecewise(points) => {
+                let mut last = (0., 0.);
+                points
+                    .iter()
+                    .copied()
+                    .chain(std::iter::once((1., 1.)))
+                    .find_map(|point| {
+                        if scaled <= point.0 {
+                            let factor = inv_lerp(last.0, point.0, scaled);
+                            Some(lerp(
+                                last.1,
+                                point.1,
+                                if factor.is_finite() { factor } else { 1. },
+                            ))
+                        } else {
+                            last = point;
+                            None
+                        }
+                    })
+                    .unwrap_or(1.)
+            }
+        };
+
+        lerp(processing.anti_deadzone, 1., curved)
+    };
+
+    let radial = |position: Vector2<f32>| {
+        let distance = position.magnitude();
+        if distance == 0. {
+            position
+        } else {
+            position * (scale(distance) / distance)
+        }
+    };
+
+    match processing.deadzone_shape {
+        DeadzoneShape::Radial => radial(position),
+        DeadzoneShape::Axial => {
+            let axial = position.map(|axis| scale(axis.abs()).copysign(axis));
+            //Diagonals can exceed the unit circle after scaling each axis
+            if axial.magnitude_squared() > 1. {
+                axial.normalize()
+            } else {
+                axial
+            }
+        }
+        DeadzoneShape::Hybrid => {
+            radial(position.map(|axis| if axis.abs() <= inner { 0. } else { axis }))
+        }
+    }
+}
+
+fn stick_axis(
+    axis: StickAxis,
+    processing: &JoystickProcessing,
+    invert: bool,
+    position: Vector2<f32>,
+) -> f32 {
+    let position = process_joystick(processing, position);
+    let state = match axis {
+        StickAxis::X => position.x,
+        StickAxis::Y => position.y,
+    };
+
+    if invert {
+        -state
+    } else {
+        state
+    }
+}
+
+fn stick_region_contains(region: &StickRegion, position: Vector2<f32>) -> bool {
+    let distance = position.magnitude();
+
+    match *region {
+        StickRegion::Direction {
+            direction,
+            sectors,
+            threshold,
+        } => {
+            let sector_width = match sectors {
+                Sectors::Four => FRAC_PI_2,
+                Sectors::Eight => FRAC_PI_4,
+            };
+            //0 is up and clockwise is positive
+            let angle = position.x.atan2(position.y);
+
+            distance >= threshold
+                && wrap_angle(angle - direction.angle()).abs() <= sector_width / 2.
+        }
+        StickRegion::OuterRing { threshold } => distance >= threshold,
+        StickRegion::InnerRing {
+            deadzone,
+            threshold,
+        } => distance > deadzone && distance < threshold,
+    }
+}
+
+/// The index of each finger down inside the region, or just `finger` if set, and its position within the region
+fn touchpad_points<'a>(
+    state: &'a TouchpadState,
+    region: &'a TouchpadRegion,
+    finger: Option<usize>,
+) -> impl Iterator<Item = (usize, Vector2<f32>)> + 'a {
+    state
+        .down_points()
+        .filter(move |(idx, point)| {
+            finger.is_none_or(|finger| finger == *idx)
+                && region.contains(point.position.x, point.position.y)
+        })
+        .map(|(idx, point)| {
+            let (x, y) = region.normalize(point.position.x, point.position.y);
+            (idx, Vector2::new(x, y))
+        })
+}
+
+/// Maps the first finger in the region to -1..1 on both axes with up being positive, like a joystick
+fn touchpad_stick(
+    state: &TouchpadState,
+    region: &TouchpadRegion,
+    finger: Option<usize>,
+) -> Vector2<f32> {
+    touchpad_points(state, region, finger)
+        .next()
+        .map(|(_, position)| Vector2::new(position.x * 2. - 1., 1. - position.y * 2.))
+        .unwrap_or_default()
+}
+
+//http://gyrowiki.jibbsmart.com/blog:good-gyro-controls-part-1:the-gyro-is-a-mouse
+/// Cuts off and smooths small angular velocities
+fn filter_gyro(
+    filtering: &GyroFiltering,
+    smoothing_buffer: &mut VecDeque<(Instant, Vector2<f32>)>,
+    velocity: Vector2<f32>,
+    now: Instant,
+) -> Vector2<f32> {
+    let speed = velocity.magnitude();
+
+    let velocity = if speed <= filtering.cut_off_speed {
+        Vector2::zeros()
+    } else if speed < filtering.cut_off_speed + filtering.cut_off_recovery {
+        velocity
+            * inv_lerp(
+                filtering.cut_off_speed,
+                filtering.cut_off_speed + filtering.cut_off_recovery,
+                speed,
+            )
+    } else {
+        velocity
+    };
+
+    //Soft tiered smoothing, slow movements are averaged over the smoothing window and fast ones pass through directly
+    let direct_weight = if filtering.smooth_threshold > 0. {
+        inv_lerp(
+            filtering.smooth_threshold / 2.,
+            filtering.smooth_threshold,
+            speed,
+        )
+        .clamp(0., 1.)
+    } else {
+        1.
+    };
+
+    let smooth_time = Duration::from_secs_f32(filtering.smooth_time.max(0.));
+    while let Some((time, _)) = smoothing_buffer.front() {
+        if now.duration_since(*time) > smooth_time {
+            smoothing_buffer.pop_front();
+        } else {
+            break;
+        }
+    }
+    smoothing_buffer.push_back((now, velocity * (1. - direct_weight)));
+
+    let smoothed = smoothing_buffer
+        .iter()
+        .fold(Vector2::zeros(), |sum, (_, velocity)| sum + velocity)
+        / smoothing_buffer.len() as f32;
+
+    velocity * direct_weight + smoothed
+}
+
 fn lerp(a: f32, b: f32, d: f32) -> f32 {
     a + (b - a) * d
 }
@@ -343,3 +1252,146 @@ fn lerp(a: f32, b: f32, d: f32) -> f32 {
 fn inv_lerp(a: f32, b: f32, v: f32) -> f32 {
     (v - a) / (b - a)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn processing(inner: f32, outer: f32, curve: ResponseCurve) -> JoystickProcessing {
+        JoystickProcessing {
+            inner_deadzone: inner,
+            outer_deadzone: outer,
+            curve,
+            ..Default::default()
+        }
+    }
+
+    fn assert_close(left: f32, right: f32) {
+        assert!((left - right).abs() < 1e-5, "{left} != {right}");
+    }
+
+    #[test]
+    fn radial_deadzones() {
+        let processing = processing(0.1, 0.2, ResponseCurve::Linear);
+
+        assert_eq!(
+            process_joystick(&processing, Vector2::new(0.05, 0.)),
+            Vector2::zeros()
+        );
+        assert_close(process_joystick(&processing, Vector2::new(0.45, 0.)).x, 0.5);
+        assert_close(process_joystick(&processing, Vector2::new(0., 0.9)).y, 1.);
+
+        //The direction is kept
+        let diagonal = process_joystick(&processing, Vector2::new(-0.318198, -0.318198));
+        assert_close(diagonal.magnitude(), 0.5);
+        assert_close(diagonal.x, diagonal.y);
+    }
+
+    #[test]
+    fn anti_deadzone() {
+        let processing = JoystickProcessing {
+            inner_deadzone: 0.1,
+            anti_deadzone: 0.2,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            process_joystick(&processing, Vector2::new(0.1, 0.)),
+            Vector2::zeros()
+        );
+        assert_close(
+            process_joystick(&processing, Vector2::new(0.100001, 0.)).x,
+            0.2,
+        );
+        assert_close(process_joystick(&processing, Vector2::new(1., 0.)).x, 1.);
+    }
+
+    #[test]
+    fn power_curve() {
+        let processing = processing(0., 0., ResponseCurve::Power(2.));
+
+        assert_close(process_joystick(&processing, Vector2::new(0.5, 0.)).x, 0.25);
+        assert_close(process_joystick(&processing, Vector2::new(1., 0.)).x, 1.);
+    }
+
+    #[test]
+    fn piecewise_curve() {
+        let processing = processing(
+            0.,
+            0.,
+            ResponseCurve::Piecewise(vec![(0.5, 0.2), (0.5, 0.4), (0.8, 0.5)]),
+        );
+
+        assert_close(process_joystick(&processing, Vector2::new(0.25, 0.)).x, 0.1);
+        assert_close(process_joystick(&processing, Vector2::new(0.5, 0.)).x, 0.2);
+        assert_close(
+            process_joystick(&processing, Vector2::new(0.65, 0.)).x,
+            0.45,
+        );
+        assert_close(process_joystick(&processing, Vector2::new(0.9, 0.)).x, 0.75);
+    }
+
+    #[test]
+    fn axial_deadzone() {
+        let processing = JoystickProcessing {
+            deadzone_shape: DeadzoneShape::Axial,
+            inner_deadzone: 0.2,
+            ..Default::default()
+        };
+
+        let position = process_joystick(&processing, Vector2::new(0.1, 0.6));
+        assert_eq!(position.x, 0.);
+        assert_close(position.y, 0.5);
+
+        //Scaled diagonals are clamped to the unit circle
+        assert_close(
+            process_joystick(&processing, Vector2::new(1., 1.)).magnitude(),
+            1.,
+        );
+    }
+
+    #[test]
+    fn joystick_processing_validation() {
+        assert!(is_valid_joystick_processing(&JoystickProcessing::default()));
+        assert!(is_valid_joystick_processing(&processing(
+            0.1,
+            0.1,
+            ResponseCurve::Piecewise(vec![(0.2, 0.1), (0.2, 0.3), (1., 1.)])
+        )));
+
+        assert!(!is_valid_joystick_processing(&processing(
+            0.5,
+            0.5,
+            ResponseCurve::Linear
+        )));
+        assert!(!is_valid_joystick_processing(&processing(
+            -0.1,
+            0.,
+            ResponseCurve::Linear
+        )));
+        assert!(!is_valid_joystick_processing(&processing(
+            0.,
+            0.,
+            ResponseCurve::Power(0.)
+        )));
+        assert!(!is_valid_joystick_processing(&processing(
+            0.,
+            0.,
+            ResponseCurve::Power(f32::NAN)
+        )));
+        assert!(!is_valid_joystick_processing(&processing(
+            0.,
+            0.,
+            ResponseCurve::Piecewise(vec![(0.6, 0.5), (0.4, 0.6)])
+        )));
+        assert!(!is_valid_joystick_processing(&processing(
+            0.,
+            0.,
+            ResponseCurve::Piecewise(vec![(0.5, 1.5)])
+        )));
+        assert!(!is_valid_joystick_processing(&JoystickProcessing {
+            anti_deadzone: 1.,
+            ..Default::default()
+        }));
+    }
+}
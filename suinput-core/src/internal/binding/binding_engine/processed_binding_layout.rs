@@ -1,11 +1,20 @@
+use std::collections::VecDeque;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use std::vec::IntoIter;
 
 use nalgebra::Vector2;
 
 use suinput_types::{
-    action::ActionStateEnum, binding::SimpleBinding, event::InputEvent, CreateBindingLayoutError,
-    SuPath,
+    action::ActionStateEnum,
+    binding::{
+        gyro::{GyroFiltering, XAxis},
+        joystick::{JoystickProcessing, Sectors, StickRegion},
+        touchpad::TouchpadRegion,
+        Binding, Sensitivity,
+    },
+    event::{InputEvent, MAX_TOUCH_POINTS},
+    CreateBindingLayoutError, SuPath,
 };
 
 use crate::action::Action;
@@ -22,12 +31,14 @@ use crate::{
         device::DeviceState,
         input_component::InputComponentType,
         interaction_profile::InteractionProfileState,
-        paths::{InputPath, InteractionProfilePath, UserPath},
+        paths::{InputPath, InteractionProfilePath, OutputPath, UserPath},
     },
 };
 
+use super::activator::ActivatorState;
 use super::processed_binding::{
-    Axis, GyroBindingSpace, ProcessedBindingType, ProcessedInputBinding, Sensitivity,
+    is_valid_joystick_processing, ChordState, GyroBindingSpace, ProcessedBindingType,
+    ProcessedInputBinding,
 };
 use super::WorkingUserInterface;
 
@@ -36,13 +47,16 @@ pub struct ProcessedBindingLayout {
     pub(crate) bindings_index: Vec<ProcessedInputBinding>,
     bindings_for_action: HashMap<u64, Vec<usize>>,
     bindings_for_input: HashMap<(UserPath, InputPath), (Vec<usize>, u32)>,
+    /// The bindings chorded on each modifier input
+    chords_for_input: HashMap<(UserPath, InputPath), Vec<usize>>,
+    output_bindings: HashMap<u64, Vec<(UserPath, OutputPath)>>,
 }
 
 impl ProcessedBindingLayout {
     pub fn new(
         instance: &Instance,
         interaction_profile: InteractionProfilePath,
-        bindings: &Vec<SimpleBinding>,
+        bindings: &[Binding],
     ) -> Result<Self, CreateBindingLayoutError> {
         let runtime = instance.runtime.upgrade().unwrap();
         let actions = instance.actions.read();
@@ -60,13 +74,18 @@ impl ProcessedBindingLayout {
         let mut bindings_index = Vec::<ProcessedInputBinding>::new();
         let mut input_bindings = HashMap::<(UserPath, InputPath), (Vec<usize>, u32)>::new();
         let mut bindings_for_action = HashMap::<u64, Vec<usize>>::new();
+        let mut output_bindings = HashMap::<u64, Vec<(UserPath, OutputPath)>>::new();
+        let mut chords_for_input = HashMap::<(UserPath, InputPath), Vec<usize>>::new();
 
-        for binding in bindings {
+        let split_path = |path: SuPath| {
             let path_string = instance
-                .get_path_string(binding.path)
-                .ok_or(CreateBindingLayoutError::InvalidPathHandle(binding.path))?;
+                .get_path_string(path)
+                .ok_or(CreateBindingLayoutError::InvalidPathHandle(path))?;
 
-            let split_idx = path_string.find("/input").expect("Invalid path string");
+            let split_idx = path_string
+                .find("/input")
+                .or_else(|| path_string.find("/output"))
+                .expect("Invalid path string");
             let (user_str, component_str) = path_string.split_at(split_idx);
 
             let user_path = instance.get_path(user_str).unwrap();
@@ -82,74 +101,274 @@ impl ProcessedBindingLayout {
                 }
             };
 
-            let component_path = instance.get_path(component_str).unwrap();
+            let component_path = interaction_profile_type
+                .resolve_alias(user_path, instance.get_path(component_str).unwrap());
 
-            if !input_bindings.contains_key(&(user_path, component_path)) {
-                input_bindings.insert((user_path, component_path), (Vec::with_capacity(1), 0));
+            Ok((user_path, component_path, device))
+        };
+
+        for binding in bindings {
+            //Unwrap nested chords down to the binding they gate
+            let mut chords = Vec::new();
+            let mut inner_binding = binding;
+            while let Binding::Chord(chord) = inner_binding {
+                let (user_path, modifier_path, device) = split_path(chord.modifier)?;
+
+                match device.input_components.get(&modifier_path) {
+                    Some(InputComponentType::Button) => chords.push(ChordState::new(
+                        (user_path, modifier_path),
+                        chord.ty,
+                        chord.threshold,
+                    )),
+                    Some(InputComponentType::Trigger) if (0. ..1.).contains(&chord.threshold) => {
+                        chords.push(ChordState::new(
+                            (user_path, modifier_path),
+                            chord.ty,
+                            chord.threshold,
+                        ))
+                    }
+                    _ => return Err(CreateBindingLayoutError::BadBinding(binding.clone())),
+                }
+
+                inner_binding = &chord.binding;
             }
 
-            let action = actions.get((binding.action as usize) - 1).ok_or(
-                CreateBindingLayoutError::InvalidActionHandle(binding.action),
+            let (binding_action, binding_path, activator) = match inner_binding {
+                Binding::Simple(simple) => (simple.action, simple.path, None),
+                Binding::Boolean(boolean) => {
+                    (boolean.output, boolean.input, Some(boolean.activator))
+                }
+                Binding::Gyro(gyro) => (gyro.output, gyro.input, None),
+                Binding::Joystick(joystick) => (joystick.output, joystick.input, None),
+                Binding::FlickStick(flick_stick) => (flick_stick.output, flick_stick.input, None),
+                Binding::StickCamera(stick_camera) => {
+                    (stick_camera.output, stick_camera.input, None)
+                }
+                Binding::StickRegion(stick_region) => {
+                    (stick_region.output, stick_region.input, None)
+                }
+                Binding::StickAxis(stick_axis) => (stick_axis.output, stick_axis.input, None),
+                Binding::Trigger(trigger) => (trigger.output, trigger.input, None),
+                Binding::Touchpad(touchpad) => (touchpad.output, touchpad.input, None),
+                Binding::Chord(_) => unreachable!(),
+            };
+
+            let (user_path, component_path, device) = split_path(binding_path)?;
+
+            let action = actions.get((binding_action as usize) - 1).ok_or(
+                CreateBindingLayoutError::InvalidActionHandle(binding_action),
             )?;
 
+            //Activators only apply to boolean actions
+            if activator.is_some() && action.data_type != ActionTypeEnum::Boolean {
+                return Err(CreateBindingLayoutError::BadBinding(binding.clone()));
+            }
+
+            if device.output_components.contains_key(&component_path) {
+                if action.data_type != ActionTypeEnum::Output || !chords.is_empty() {
+                    return Err(CreateBindingLayoutError::BadBinding(binding.clone()));
+                }
+
+                output_bindings
+                    .entry(action.handle)
+                    .or_default()
+                    .push((user_path, component_path));
+                continue;
+            }
+
+            if !input_bindings.contains_key(&(user_path, component_path)) {
+                input_bindings.insert((user_path, component_path), (Vec::with_capacity(1), 0));
+            }
+
             let processed_binding = match device.input_components.get(&component_path) {
                 Some(InputComponentType::Button) => {
                     if action.data_type == ActionTypeEnum::Boolean {
                         ProcessedBindingType::Button2Bool
                     } else if action.data_type == ActionTypeEnum::Value {
                         ProcessedBindingType::Button2Value
+                    } else if action.data_type == ActionTypeEnum::Axis1d {
+                        ProcessedBindingType::Button2Axis1d
                     } else {
-                        return Err(CreateBindingLayoutError::BadBinding(*binding));
+                        return Err(CreateBindingLayoutError::BadBinding(binding.clone()));
                     }
                 }
                 Some(InputComponentType::Trigger) => {
                     if action.data_type == ActionTypeEnum::Boolean {
-                        ProcessedBindingType::Trigger2Bool
+                        let (press_threshold, release_threshold) = match inner_binding {
+                            Binding::Trigger(trigger) => {
+                                (trigger.press_threshold, trigger.release_threshold)
+                            }
+                            _ => (0.5, 0.5),
+                        };
+
+                        ProcessedBindingType::Trigger2Bool {
+                            press_threshold,
+                            release_threshold,
+                            pressed: false,
+                        }
                     } else if action.data_type == ActionTypeEnum::Value {
                         ProcessedBindingType::Trigger2Value
+                    } else if action.data_type == ActionTypeEnum::Axis1d {
+                        ProcessedBindingType::Trigger2Axis1d
                     } else {
-                        return Err(CreateBindingLayoutError::BadBinding(*binding));
+                        return Err(CreateBindingLayoutError::BadBinding(binding.clone()));
                     }
                 }
                 Some(InputComponentType::Move2D) => {
                     if action.data_type != ActionTypeEnum::Delta2d {
-                        return Err(CreateBindingLayoutError::BadBinding(*binding));
+                        return Err(CreateBindingLayoutError::BadBinding(binding.clone()));
                     }
 
                     ProcessedBindingType::Move2d2Delta2d {
                         sensitivity: (1., 1.),
                     }
                 }
-                Some(InputComponentType::Joystick) => {
-                    if action.data_type != ActionTypeEnum::Axis2d {
-                        return Err(CreateBindingLayoutError::BadBinding(*binding));
+                Some(InputComponentType::Cursor) => {
+                    if action.data_type != ActionTypeEnum::Cursor {
+                        return Err(CreateBindingLayoutError::BadBinding(binding.clone()));
                     }
 
-                    ProcessedBindingType::Joystick2Axis2d
+                    ProcessedBindingType::Cursor2Cursor
+                }
+                Some(InputComponentType::Joystick) => match (action.data_type, inner_binding) {
+                    (ActionTypeEnum::Axis2d, _) => ProcessedBindingType::Joystick2Axis2d {
+                        processing: match inner_binding {
+                            Binding::Joystick(joystick) => joystick.processing.clone(),
+                            _ => JoystickProcessing::default(),
+                        },
+                    },
+                    (ActionTypeEnum::Axis1d, Binding::StickAxis(stick_axis)) => {
+                        ProcessedBindingType::Joystick2Axis1d {
+                            axis: stick_axis.axis,
+                            processing: stick_axis.processing.clone(),
+                            invert: stick_axis.invert,
+                        }
+                    }
+                    (ActionTypeEnum::Boolean, Binding::StickRegion(stick_region)) => {
+                        //Diagonals don't have a sector of their own with four sectors
+                        if let StickRegion::Direction {
+                            direction,
+                            sectors: Sectors::Four,
+                            ..
+                        } = stick_region.region
+                        {
+                            if direction.is_diagonal() {
+                                return Err(CreateBindingLayoutError::BadBinding(binding.clone()));
+                            }
+                        }
+
+                        ProcessedBindingType::Joystick2Bool {
+                            region: stick_region.region,
+                            pressed: false,
+                        }
+                    }
+                    (ActionTypeEnum::Delta2d, Binding::FlickStick(flick_stick)) => {
+                        ProcessedBindingType::Joystick2Delta2d {
+                            flick_time: Duration::from_secs_f32(flick_stick.flick_time.max(0.)),
+                            threshold: flick_stick.threshold,
+                            sensitivity: flick_stick.sensitivity,
+                            last_angle: None,
+                            flick: None,
+                        }
+                    }
+                    (ActionTypeEnum::Delta2d, Binding::StickCamera(stick_camera)) => {
+                        ProcessedBindingType::Joystick2CameraDelta2d {
+                            processing: stick_camera.processing.clone(),
+                            sensitivity: stick_camera.sensitivity,
+                            edge_acceleration: stick_camera.edge_acceleration,
+                            position: Vector2::zeros(),
+                            last_time: None,
+                            edge_time: None,
+                        }
+                    }
+                    _ => return Err(CreateBindingLayoutError::BadBinding(binding.clone())),
+                },
+                Some(InputComponentType::Touchpad { max_points, .. }) => {
+                    let (region, finger, sensitivity) = match inner_binding {
+                        Binding::Touchpad(touchpad) => {
+                            (touchpad.region, touchpad.finger, touchpad.sensitivity)
+                        }
+                        _ => (TouchpadRegion::default(), None, (1., 1.)),
+                    };
+
+                    let valid_region = 0. <= region.left
+                        && region.left < region.right
+                        && region.right <= 1.
+                        && 0. <= region.top
+                        && region.top < region.bottom
+                        && region.bottom <= 1.;
+                    let valid_finger = finger
+                        .is_none_or(|finger| finger < (*max_points as usize).min(MAX_TOUCH_POINTS));
+                    if !valid_region || !valid_finger {
+                        return Err(CreateBindingLayoutError::BadBinding(binding.clone()));
+                    }
+
+                    match action.data_type {
+                        ActionTypeEnum::Boolean => ProcessedBindingType::Touchpad2Bool {
+                            region,
+                            finger,
+                            pressed: false,
+                        },
+                        ActionTypeEnum::Axis2d => {
+                            ProcessedBindingType::Touchpad2Axis2d { region, finger }
+                        }
+                        ActionTypeEnum::Cursor => {
+                            ProcessedBindingType::Touchpad2Cursor { region, finger }
+                        }
+                        ActionTypeEnum::Delta2d => ProcessedBindingType::Touchpad2Delta2d {
+                            region,
+                            finger,
+                            sensitivity,
+                            tracked: None,
+                        },
+                        _ => return Err(CreateBindingLayoutError::BadBinding(binding.clone())),
+                    }
+                }
+                Some(InputComponentType::Gyro(_)) if action.data_type == ActionTypeEnum::Pose => {
+                    //Orientation needs an accelerometer to correct for drift
+                    if device.accel.is_none() {
+                        return Err(CreateBindingLayoutError::BadBinding(binding.clone()));
+                    }
+
+                    ProcessedBindingType::Gyro2Pose
                 }
                 Some(InputComponentType::Gyro(_)) => {
                     if action.data_type != ActionTypeEnum::Delta2d {
-                        return Err(CreateBindingLayoutError::BadBinding(*binding));
+                        return Err(CreateBindingLayoutError::BadBinding(binding.clone()));
                     }
 
-                    //TODO default depending on controller type somehow
-                    //Handheld -> Local
-                    //Controller -> Player
+                    let (space, filtering, sensitivity, invert) = match inner_binding {
+                        Binding::Gyro(gyro) => (
+                            gyro.space.into(),
+                            gyro.filtering,
+                            gyro.sensitivity,
+                            (gyro.invert_x, gyro.invert_y),
+                        ),
+                        //TODO default depending on controller type somehow
+                        //Handheld -> Local
+                        //Controller -> Player
+                        _ => (
+                            GyroBindingSpace::PlayerSpace {
+                                relax_factor: GyroBindingSpace::calc_relax_factor(60.),
+                                x_axis: XAxis::Yaw,
+                            },
+                            GyroFiltering::default(),
+                            Sensitivity::Linear(1.),
+                            (false, false),
+                        ),
+                    };
+
                     ProcessedBindingType::Gyro2Delta2d {
                         last_time: None,
-                        space: GyroBindingSpace::PlayerSpace {
-                            relax_factor: GyroBindingSpace::calc_relax_factor(60.),
-                            x_axis: Axis::Yaw,
-                        },
-                        // cut_off_speed: 0.,
-                        // cut_off_recovery: 0.,
-                        // smooth_threshold: 0.,
-                        // smooth_time: 0.125,
-                        sensitivity: Sensitivity::Linear(1.),
+                        space,
+                        filtering,
+                        smoothing_buffer: VecDeque::new(),
+                        sensitivity,
+                        invert,
                     }
                 }
                 Some(InputComponentType::Accel) => {
-                    return Err(CreateBindingLayoutError::BadBinding(*binding));
+                    return Err(CreateBindingLayoutError::BadBinding(binding.clone()));
                 }
                 None => {
                     return Err(instance.get_path_string(component_path).map_or(
@@ -159,6 +378,86 @@ impl ProcessedBindingLayout {
                 }
             };
 
+            let valid = match &processed_binding {
+                ProcessedBindingType::Joystick2Axis2d { processing }
+                | ProcessedBindingType::Joystick2Axis1d { processing, .. }
+                | ProcessedBindingType::Joystick2CameraDelta2d { processing, .. } => {
+                    is_valid_joystick_processing(processing)
+                }
+                //A release threshold above the press threshold would make the button flicker
+                ProcessedBindingType::Trigger2Bool {
+                    press_threshold,
+                    release_threshold,
+                    ..
+                } => (0. ..=*press_threshold).contains(release_threshold) && *press_threshold < 1.,
+                ProcessedBindingType::Joystick2Bool { region, .. } => match *region {
+                    StickRegion::Direction { threshold, .. }
+                    | StickRegion::OuterRing { threshold } => 0. < threshold && threshold <= 1.,
+                    StickRegion::InnerRing {
+                        deadzone,
+                        threshold,
+                    } => 0. <= deadzone && deadzone < threshold && threshold <= 1.,
+                },
+                ProcessedBindingType::Joystick2Delta2d { threshold, .. } => {
+                    0. < *threshold && *threshold <= 1.
+                }
+                _ => true,
+            };
+
+            if !valid {
+                return Err(CreateBindingLayoutError::BadBinding(binding.clone()));
+            }
+
+            //Typed bindings only configure their own kind of component
+            let matches_component = match inner_binding {
+                Binding::Gyro(_) => {
+                    matches!(processed_binding, ProcessedBindingType::Gyro2Delta2d { .. })
+                }
+                Binding::Joystick(_) => {
+                    matches!(
+                        processed_binding,
+                        ProcessedBindingType::Joystick2Axis2d { .. }
+                    )
+                }
+                Binding::FlickStick(_) => {
+                    matches!(
+                        processed_binding,
+                        ProcessedBindingType::Joystick2Delta2d { .. }
+                    )
+                }
+                Binding::StickCamera(_) => matches!(
+                    processed_binding,
+                    ProcessedBindingType::Joystick2CameraDelta2d { .. }
+                ),
+                Binding::StickRegion(_) => {
+                    matches!(
+                        processed_binding,
+                        ProcessedBindingType::Joystick2Bool { .. }
+                    )
+                }
+                Binding::StickAxis(_) => {
+                    matches!(
+                        processed_binding,
+                        ProcessedBindingType::Joystick2Axis1d { .. }
+                    )
+                }
+                Binding::Trigger(_) => {
+                    matches!(processed_binding, ProcessedBindingType::Trigger2Bool { .. })
+                }
+                Binding::Touchpad(_) => matches!(
+                    processed_binding,
+                    ProcessedBindingType::Touchpad2Bool { .. }
+                        | ProcessedBindingType::Touchpad2Axis2d { .. }
+                        | ProcessedBindingType::Touchpad2Cursor { .. }
+                        | ProcessedBindingType::Touchpad2Delta2d { .. }
+                ),
+                _ => true,
+            };
+
+            if !matches_component {
+                return Err(CreateBindingLayoutError::BadBinding(binding.clone()));
+            }
+
             let action_state = match action.data_type {
                 ActionTypeEnum::Boolean => InternalActionState::Boolean(false),
                 ActionTypeEnum::Axis1d => InternalActionState::Axis1d(0.),
@@ -172,7 +471,16 @@ impl ProcessedBindingLayout {
                 state: action_state,
                 action: action.handle,
                 input_component: (user_path, component_path),
+                activator: activator.map(ActivatorState::new),
+                chords,
+                chord_open: true,
             });
+            for chord in &bindings_index.last().unwrap().chords {
+                chords_for_input
+                    .entry(chord.modifier)
+                    .or_default()
+                    .push(bindings_index.len() - 1);
+            }
             input_bindings
                 .get_mut(&(user_path, component_path))
                 .unwrap()
@@ -186,13 +494,79 @@ impl ProcessedBindingLayout {
             }
         }
 
+        //Blocking activators hold back the other activators bound to the same input
+        for (input_bindings, _) in input_bindings.values() {
+            let (hold_delay, tap_delay) = input_bindings
+                .iter()
+                .filter_map(|&idx| bindings_index[idx].activator.as_ref())
+                .map(ActivatorState::blocking_delays)
+                .fold((None, None), |(hold, tap), (new_hold, new_tap)| {
+                    (hold.max(new_hold), tap.max(new_tap))
+                });
+
+            for &idx in input_bindings {
+                if let Some(activator) = &mut bindings_index[idx].activator {
+                    activator.hold_delay = hold_delay;
+                    activator.tap_delay = tap_delay;
+                }
+            }
+
+            Self::open_chord_gates(&mut bindings_index, input_bindings);
+        }
+
         Ok(Self {
             bindings_index,
             bindings_for_input: input_bindings,
             bindings_for_action,
+            chords_for_input,
+            output_bindings,
         })
     }
 
+    /// Opens the bindings of an input that are the most specific of those not gated by a chord
+    fn open_chord_gates(bindings_index: &mut [ProcessedInputBinding], bindings: &[usize]) {
+        let max_specificity = Self::max_chord_specificity(bindings_index, bindings);
+        for &idx in bindings {
+            let binding = &mut bindings_index[idx];
+            let specificity = binding.chord_specificity();
+            binding.chord_open = specificity.is_some() && specificity == max_specificity;
+        }
+    }
+
+    /// Takes on the state of modifiers that were already held when the layout is attached, without toggling
+    pub(crate) fn seed_chords(&mut self, interaction_profile: &InteractionProfileState) {
+        for binding in &mut self.bindings_index {
+            for chord in &mut binding.chords {
+                chord.held = interaction_profile
+                    .get_input_component_state(chord.modifier.0, chord.modifier.1)
+                    .and_then(|data| chord.is_held_state(&data.state))
+                    .unwrap_or(false);
+            }
+        }
+
+        for (bindings, _) in self.bindings_for_input.values() {
+            Self::open_chord_gates(&mut self.bindings_index, bindings);
+        }
+    }
+
+    /// Chorded bindings shadow the less specific bindings on the same input so a bare key doesn't fire with its combo
+    fn max_chord_specificity(
+        bindings_index: &[ProcessedInputBinding],
+        bindings: &[usize],
+    ) -> Option<usize> {
+        bindings
+            .iter()
+            .filter_map(|&idx| bindings_index[idx].chord_specificity())
+            .max()
+    }
+
+    /// The output components bound to an output action
+    pub(crate) fn get_output_bindings(&self, action: u64) -> &[(UserPath, OutputPath)] {
+        self.output_bindings
+            .get(&action)
+            .map_or(&[], |bindings| bindings.as_slice())
+    }
+
     pub fn convert_from_binding_layout(
         _instance: &Instance,
         interaction_profile: InteractionProfilePath,
@@ -214,13 +588,39 @@ impl ProcessedBindingLayout {
         devices: &ParallelArena<(DeviceState, InteractionProfilePath)>,
         interface: &mut WorkingUserInterface,
     ) {
+        if let Some(chorded_bindings) = self.chords_for_input.get(&(user_path, event.path)) {
+            let mut gated_inputs = Vec::new();
+
+            for &binding_index in chorded_bindings {
+                let binding = &mut self.bindings_index[binding_index];
+
+                for chord in binding
+                    .chords
+                    .iter_mut()
+                    .filter(|chord| chord.modifier == (user_path, event.path))
+                {
+                    if let Some(held) = chord.is_held(&event.data) {
+                        chord.on_modifier(held);
+
+                        if !gated_inputs.contains(&binding.input_component) {
+                            gated_inputs.push(binding.input_component);
+                        }
+                    }
+                }
+            }
+
+            for input_component in gated_inputs {
+                self.update_chord_gates(input_component, interaction_profile, interface);
+            }
+        }
+
         if let Some((bindings, max_priority)) =
             self.bindings_for_input.get(&(user_path, event.path))
         {
             for &binding_index in bindings {
                 let binding = &mut self.bindings_index[binding_index];
 
-                if !interface.is_action_active(binding.action) {
+                if !interface.is_action_active(binding.action) || !binding.chord_open {
                     continue;
                 }
 
@@ -230,27 +630,251 @@ impl ProcessedBindingLayout {
                     continue;
                 }
 
-                let action = binding.action;
+                if let Some(new_binding_state) = binding.ty.on_event(
+                    user_path,
+                    event,
+                    interaction_profile,
+                    devices,
+                    interface.window,
+                ) {
+                    let (new_binding_state, overriding) =
+                        binding.apply_activator(new_binding_state, Instant::now());
+
+                    if let Some(new_binding_state) = new_binding_state {
+                        Self::fire_binding_event(
+                            interface,
+                            &mut self.bindings_index,
+                            &self.bindings_for_action,
+                            binding_index,
+                            new_binding_state,
+                        );
+                    }
+
+                    if overriding {
+                        Self::override_activators(
+                            interface,
+                            &mut self.bindings_index,
+                            &self.bindings_for_action,
+                            bindings,
+                            binding_index,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Interrupts bindings that a chord closed on and activates the ones it opened
+    fn update_chord_gates(
+        &mut self,
+        input_component: (UserPath, InputPath),
+        interaction_profile: &InteractionProfileState,
+        interface: &mut WorkingUserInterface,
+    ) {
+        let (bindings, max_priority) = self.bindings_for_input.get(&input_component).unwrap();
+        let max_specificity = Self::max_chord_specificity(&self.bindings_index, bindings);
+
+        for &binding_index in bindings {
+            let binding = &mut self.bindings_index[binding_index];
+
+            let specificity = binding.chord_specificity();
+            let open = specificity.is_some() && specificity == max_specificity;
+            if open == binding.chord_open {
+                continue;
+            }
+            binding.chord_open = open;
+
+            if !interface.is_action_active(binding.action)
+                || interface.get_action_priority(binding.action) < *max_priority
+            {
+                continue;
+            }
+
+            let event = if open {
+                interaction_profile
+                    .get_input_component_state(input_component.0, input_component.1)
+                    .and_then(|component_state| binding.activate(component_state, Instant::now()))
+            } else {
+                binding.interrupt()
+            };
 
-                if let Some(new_binding_state) =
-                    binding
-                        .ty
-                        .on_event(user_path, event, interaction_profile, devices)
+            if let Some(event) = event {
+                Self::fire_binding_event(
+                    interface,
+                    &mut self.bindings_index,
+                    &self.bindings_for_action,
+                    binding_index,
+                    event,
+                );
+            }
+        }
+    }
+
+    /// Advances time based bindings, releases activator impulses and fires time based activators
+    pub(crate) fn on_tick(&mut self, interface: &mut WorkingUserInterface) {
+        let now = Instant::now();
+
+        for (bindings, max_priority) in self.bindings_for_input.values() {
+            for &binding_index in bindings {
+                let binding = &mut self.bindings_index[binding_index];
+
+                if !interface.is_action_active(binding.action)
+                    || interface.get_action_priority(binding.action) < *max_priority
+                    || !binding.chord_open
                 {
-                    binding.save_state(&new_binding_state);
-                    let new_binding_state = Self::aggregate(
+                    continue;
+                }
+
+                if let Some(event) = binding.ty.on_tick(now) {
+                    Self::fire_binding_event(
                         interface,
-                        new_binding_state,
-                        binding.action,
-                        &self.bindings_index,
+                        &mut self.bindings_index,
                         &self.bindings_for_action,
                         binding_index,
+                        event,
                     );
+                }
 
-                    if let Some(new_binding_state) = new_binding_state {
-                        interface.fire_action_event(action, new_binding_state)
-                    }
+                let binding = &mut self.bindings_index[binding_index];
+                let output = match &mut binding.activator {
+                    Some(activator) => activator.on_tick(now),
+                    None => continue,
+                };
+
+                if let Some(event) = output.event {
+                    Self::fire_binding_event(
+                        interface,
+                        &mut self.bindings_index,
+                        &self.bindings_for_action,
+                        binding_index,
+                        ActionStateEnum::Boolean(event),
+                    );
+                }
+
+                if output.overriding {
+                    Self::override_activators(
+                        interface,
+                        &mut self.bindings_index,
+                        &self.bindings_for_action,
+                        bindings,
+                        binding_index,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Activates the bindings of inputs that are already held
+    pub(crate) fn activate_held_inputs(
+        &mut self,
+        interaction_profile: &InteractionProfileState,
+        interface: &mut WorkingUserInterface,
+    ) {
+        self.seed_chords(interaction_profile);
+
+        for (input_component, (bindings, max_priority)) in &self.bindings_for_input {
+            let component_state = match interaction_profile
+                .get_input_component_state(input_component.0, input_component.1)
+            {
+                Some(component_state) => component_state,
+                None => continue,
+            };
+
+            for &binding_index in bindings {
+                let binding = &mut self.bindings_index[binding_index];
+
+                //Bindings activated when their action set was enabled are skipped
+                if !interface.is_action_active(binding.action)
+                    || interface.get_action_priority(binding.action) < *max_priority
+                    || !binding.state.is_idle()
+                {
+                    continue;
                 }
+
+                if let Some(event) = binding.activate(component_state, Instant::now()) {
+                    Self::fire_binding_event(
+                        interface,
+                        &mut self.bindings_index,
+                        &self.bindings_for_action,
+                        binding_index,
+                        event,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Interrupts every binding, used when the layout is detached from the session
+    pub(crate) fn interrupt_all(&mut self, interface: &mut WorkingUserInterface) {
+        for binding_index in 0..self.bindings_index.len() {
+            let binding = &mut self.bindings_index[binding_index];
+
+            if !interface.is_action_active(binding.action) {
+                continue;
+            }
+
+            if let Some(event) = binding.interrupt() {
+                Self::fire_binding_event(
+                    interface,
+                    &mut self.bindings_index,
+                    &self.bindings_for_action,
+                    binding_index,
+                    event,
+                );
+            }
+        }
+    }
+
+    fn fire_binding_event(
+        interface: &mut WorkingUserInterface,
+        bindings_index: &mut [ProcessedInputBinding],
+        bindings_for_action: &HashMap<u64, Vec<usize>>,
+        binding_index: usize,
+        new_binding_state: ActionStateEnum,
+    ) {
+        let binding = &mut bindings_index[binding_index];
+        binding.save_state(&new_binding_state);
+        let action = binding.action;
+
+        if let Some(new_binding_state) = Self::aggregate(
+            interface,
+            new_binding_state,
+            action,
+            bindings_index,
+            bindings_for_action,
+            binding_index,
+        ) {
+            interface.fire_action_event(action, new_binding_state)
+        }
+    }
+
+    /// Notifies the other activators bound to an input that one of them fired
+    fn override_activators(
+        interface: &mut WorkingUserInterface,
+        bindings_index: &mut [ProcessedInputBinding],
+        bindings_for_action: &HashMap<u64, Vec<usize>>,
+        bindings: &[usize],
+        overriding_index: usize,
+    ) {
+        for &binding_index in bindings {
+            let binding = &mut bindings_index[binding_index];
+
+            if binding_index == overriding_index || !interface.is_action_active(binding.action) {
+                continue;
+            }
+
+            if let Some(event) = binding
+                .activator
+                .as_mut()
+                .and_then(|activator| activator.on_overridden())
+            {
+                Self::fire_binding_event(
+                    interface,
+                    bindings_index,
+                    bindings_for_action,
+                    binding_index,
+                    ActionStateEnum::Boolean(event),
+                );
             }
         }
     }
@@ -259,7 +883,7 @@ impl ProcessedBindingLayout {
         interface: &WorkingUserInterface,
         new_binding_state: ActionStateEnum,
         action: u64,
-        bindings_index: &Vec<ProcessedInputBinding>,
+        bindings_index: &[ProcessedInputBinding],
         bindings_for_action: &HashMap<u64, Vec<usize>>,
         binding_idx: usize,
     ) -> Option<ActionStateEnum> {
@@ -348,7 +972,7 @@ impl ProcessedBindingLayout {
                         }
 
                         if interface.get_action_priority(binding.action) == *old_max_priority {
-                            if let Some(event) = binding.ty.interrupt(&binding.state) {
+                            if let Some(event) = binding.interrupt() {
                                 binding.save_state(&event);
                                 let binding_action = binding.action;
                                 if let Some(event) = Self::aggregate(
@@ -370,7 +994,7 @@ impl ProcessedBindingLayout {
                         .get_input_component_state(input_component.0, input_component.1)
                     {
                         let binding = self.bindings_index.get_mut(*action_binding_idx).unwrap();
-                        if let Some(event) = binding.ty.activate(component_state) {
+                        if let Some(event) = binding.activate(component_state, Instant::now()) {
                             binding.save_state(&event);
                             if let Some(event) = Self::aggregate(
                                 interface,
@@ -413,7 +1037,7 @@ impl ProcessedBindingLayout {
                 //If the old max priority is the same as the action's priority
                 if *old_max_priority == action_set.default_priority {
                     //Interrupt the action's binding
-                    if let Some(event) = action_binding.ty.interrupt(&action_binding.state) {
+                    if let Some(event) = action_binding.interrupt() {
                         action_binding.save_state(&event);
                         if let Some(event) = Self::aggregate(
                             interface,
@@ -449,7 +1073,9 @@ impl ProcessedBindingLayout {
                                     && new_max_priority
                                         == interface.get_action_priority(binding.action)
                                 {
-                                    if let Some(event) = binding.ty.activate(component_state) {
+                                    if let Some(event) =
+                                        binding.activate(component_state, Instant::now())
+                                    {
                                         binding.save_state(&event);
                                         let binding_action = binding.action;
                                         if let Some(event) = Self::aggregate(
@@ -477,7 +1103,7 @@ impl ProcessedBindingLayout {
 }
 
 struct Aggregator<'a> {
-    bindings: &'a Vec<ProcessedInputBinding>,
+    bindings: &'a [ProcessedInputBinding],
     bindings_for_action: &'a HashMap<u64, Vec<usize>>,
     interface: &'a WorkingUserInterface<'a>,
 }
@@ -517,3 +1143,113 @@ impl<'a> InputEventSources for Aggregator<'a> {
             .into_iter()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use suinput_types::{binding::chord::ChordType, event::InputComponentEvent};
+
+    use super::*;
+
+    const INPUT: (UserPath, InputPath) = (SuPath(1), SuPath(2));
+    const MODIFIER: (UserPath, InputPath) = (SuPath(1), SuPath(3));
+
+    fn binding(chords: Vec<ChordState>) -> ProcessedInputBinding {
+        ProcessedInputBinding {
+            ty: ProcessedBindingType::Button2Bool,
+            state: InternalActionState::Boolean(false),
+            action: 1,
+            input_component: INPUT,
+            activator: None,
+            chords,
+            chord_open: true,
+        }
+    }
+
+    /// A bare binding and one chorded on the modifier, returning which of them are open
+    fn chord_gates(ty: ChordType, threshold: f32, modifier: &[InputComponentEvent]) -> [bool; 2] {
+        let mut bindings_index = vec![
+            binding(Vec::new()),
+            binding(vec![ChordState::new(MODIFIER, ty, threshold)]),
+        ];
+
+        for event in modifier {
+            let chord = &mut bindings_index[1].chords[0];
+            if let Some(held) = chord.is_held(event) {
+                chord.on_modifier(held);
+            }
+        }
+
+        ProcessedBindingLayout::open_chord_gates(&mut bindings_index, &[0, 1]);
+        [bindings_index[0].chord_open, bindings_index[1].chord_open]
+    }
+
+    #[test]
+    fn press_to_enable_shadows_bare_binding() {
+        use InputComponentEvent::Button;
+
+        assert_eq!(
+            chord_gates(ChordType::PressToEnable, 0., &[]),
+            [true, false]
+        );
+        assert_eq!(
+            chord_gates(ChordType::PressToEnable, 0., &[Button(true)]),
+            [false, true]
+        );
+        assert_eq!(
+            chord_gates(ChordType::PressToEnable, 0., &[Button(true), Button(false)]),
+            [true, false]
+        );
+    }
+
+    #[test]
+    fn toggle_shadows_bare_binding() {
+        use InputComponentEvent::Button;
+
+        //Open from the first press until the next one
+        assert_eq!(
+            chord_gates(ChordType::Toggle, 0., &[Button(true), Button(false)]),
+            [false, true]
+        );
+        assert_eq!(
+            chord_gates(
+                ChordType::Toggle,
+                0.,
+                &[Button(true), Button(false), Button(true), Button(false)]
+            ),
+            [true, false]
+        );
+    }
+
+    #[test]
+    fn press_to_disable_does_not_shadow() {
+        use InputComponentEvent::Button;
+
+        //Both bindings are equally specific while the modifier is released
+        assert_eq!(
+            chord_gates(ChordType::PressToDisable, 0., &[]),
+            [true, true]
+        );
+        assert_eq!(
+            chord_gates(ChordType::PressToDisable, 0., &[Button(true)]),
+            [true, false]
+        );
+    }
+
+    #[test]
+    fn trigger_modifier_threshold() {
+        use InputComponentEvent::Trigger;
+
+        assert_eq!(
+            chord_gates(ChordType::PressToEnable, 0.6, &[Trigger(0.5)]),
+            [true, false]
+        );
+        assert_eq!(
+            chord_gates(ChordType::PressToEnable, 0.6, &[Trigger(0.7)]),
+            [false, true]
+        );
+        assert_eq!(
+            chord_gates(ChordType::PressToEnable, 0.6, &[Trigger(0.7), Trigger(0.6)]),
+            [true, false]
+        );
+    }
+}
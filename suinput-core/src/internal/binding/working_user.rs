@@ -4,7 +4,7 @@ use hashbrown::HashSet;
 use suinput_types::{
     action::{ActionEvent, ActionEventEnum, ActionListener, ActionStateEnum, ChildActionType},
     event::InputEvent,
-    SuPath, Time,
+    SuPath, Time, WindowHandle,
 };
 
 use crate::{
@@ -15,10 +15,10 @@ use crate::{
             CompoundActionState, CompoundAxis1dState, CompoundAxis2dState, StickyBoolState,
         },
         parallel_arena::ParallelArena,
-        paths::InteractionProfilePath,
+        paths::{InteractionProfilePath, OutputPath, UserPath},
         types::HashMap,
     },
-    types::action_type::{Axis2d, Value},
+    types::action_type::{Axis1d, Axis2d, Value},
 };
 use crate::{
     action::{ActionCompoundType, ParentActionType},
@@ -38,6 +38,9 @@ pub struct WorkingUser {
 
     pub action_states: HashMap<u64, WorkingActionState>,
     pub compound_action_states: HashMap<u64, Box<dyn CompoundActionState>>,
+
+    /// Cursor bindings only follow this window
+    pub window: Option<WindowHandle>,
 }
 
 pub struct WorkingActionState {
@@ -58,20 +61,24 @@ impl WorkingUser {
                     .get()
                     .expect("Session created with unbaked action set")
                     .iter()
-                    .map(|action| {
+                    .filter_map(|action| {
                         let default_state = match action.data_type {
                             ActionTypeEnum::Boolean => ActionStateEnum::Boolean(false),
                             ActionTypeEnum::Delta2d => {
                                 ActionStateEnum::Delta2d(mint::Vector2 { x: 0., y: 0. })
                             }
+                            ActionTypeEnum::Cursor => ActionStateEnum::Cursor(None),
                             ActionTypeEnum::Value => ActionStateEnum::Value(0.),
                             ActionTypeEnum::Axis1d => ActionStateEnum::Axis1d(0.),
                             ActionTypeEnum::Axis2d => {
                                 ActionStateEnum::Axis2d(mint::Vector2 { x: 0., y: 0. })
                             }
+                            ActionTypeEnum::Pose => ActionStateEnum::Pose(Default::default()),
+                            //Output actions never receive binding events
+                            ActionTypeEnum::Output => return None,
                         };
 
-                        (
+                        Some((
                             action.handle,
                             WorkingActionState {
                                 state: default_state,
@@ -79,7 +86,7 @@ impl WorkingUser {
                                 priority: action_set.default_priority,
                                 last_change_time: Time(0),
                             },
-                        )
+                        ))
                     })
             })
             .collect::<HashMap<_, _>>();
@@ -125,6 +132,7 @@ impl WorkingUser {
             binding_layouts: HashMap::new(),
             action_states,
             compound_action_states,
+            window: None,
         }
     }
 
@@ -148,6 +156,7 @@ impl WorkingUser {
                 binding_layouts: &self.binding_layouts,
                 action_states: &mut self.action_states,
                 compound_action_states: &mut self.compound_action_states,
+                window: self.window,
                 callbacks,
                 actions,
                 interaction_profile_id,
@@ -160,6 +169,54 @@ impl WorkingUser {
         }
     }
 
+    /// Advances activator timers, called once per sync after input events have been processed
+    pub(crate) fn on_tick(
+        &mut self,
+        active_action_sets: &HashSet<u64>,
+        actions: &HashMap<u64, Arc<Action>>,
+        callbacks: &mut [Box<dyn ActionListener>],
+    ) {
+        for (interaction_profile_id, binding_layout_cell) in self.binding_layouts.iter() {
+            let mut attached_binding_layout_ref = binding_layout_cell.borrow_mut();
+            let attached_binding_layout = attached_binding_layout_ref.deref_mut();
+
+            let mut wui = WorkingUserInterface {
+                binding_layout_action_states: &mut attached_binding_layout.action_states,
+                binding_layouts: &self.binding_layouts,
+                action_states: &mut self.action_states,
+                compound_action_states: &mut self.compound_action_states,
+                window: self.window,
+                callbacks,
+                actions,
+                interaction_profile_id: *interaction_profile_id,
+                active_action_sets,
+            };
+
+            attached_binding_layout.binding_layout.on_tick(&mut wui);
+        }
+    }
+
+    /// Returns every output component the action is bound to along with the interaction profile of the binding layout
+    pub(crate) fn get_output_bindings(
+        &self,
+        action: u64,
+    ) -> Vec<(InteractionProfilePath, UserPath, OutputPath)> {
+        self.binding_layouts
+            .iter()
+            .flat_map(|(interaction_profile, binding_layout)| {
+                binding_layout
+                    .borrow()
+                    .binding_layout
+                    .get_output_bindings(action)
+                    .iter()
+                    .map(|(user_path, output_path)| {
+                        (*interaction_profile, *user_path, *output_path)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
     pub fn handle_binding_event(
         action_states: &mut HashMap<u64, WorkingActionState>,
         binding_layouts: &HashMap<InteractionProfilePath, RefCell<AttachedBindingLayout>>,
@@ -195,11 +252,13 @@ impl WorkingUser {
 
                 Some(ActionEventEnum::Delta2d { delta })
             }
-            ActionStateEnum::Cursor(normalized_window_coords) => {
+            //The latest cursor position wins
+            ActionStateEnum::Cursor(cursor) => {
                 action_states.get_mut(&action_handle).unwrap().state =
-                    ActionStateEnum::Cursor(normalized_window_coords);
-                Some(ActionEventEnum::Cursor {
-                    normalized_window_coords,
+                    ActionStateEnum::Cursor(cursor);
+                cursor.map(|cursor| ActionEventEnum::Cursor {
+                    normalized_window_coords: cursor.normalized_window_coords,
+                    window: cursor.window,
                 })
             }
             ActionStateEnum::Value(value) => UserActions {
@@ -212,8 +271,16 @@ impl WorkingUser {
                     ActionStateEnum::Value(value);
                 ActionEventEnum::Value { state: value }
             }),
-            //TODO support Axis1d binding endpoints
-            ActionStateEnum::Axis1d(_) => todo!(),
+            ActionStateEnum::Axis1d(state) => UserActions {
+                attached_binding_layouts: binding_layouts,
+                action_states,
+            }
+            .aggregate::<Axis1d>(action_handle, state, interaction_profile_id)
+            .map(|state| {
+                action_states.get_mut(&action_handle).unwrap().state =
+                    ActionStateEnum::Axis1d(state);
+                ActionEventEnum::Axis1d { state }
+            }),
             ActionStateEnum::Axis2d(state) => UserActions {
                 attached_binding_layouts: binding_layouts,
                 action_states,
@@ -226,6 +293,11 @@ impl WorkingUser {
                     state: state.into(),
                 }
             }),
+            //The latest pose wins
+            ActionStateEnum::Pose(pose) => {
+                action_states.get_mut(&action_handle).unwrap().state = ActionStateEnum::Pose(pose);
+                Some(ActionEventEnum::Pose { pose })
+            }
         };
 
         if let Some(event) = event {
@@ -286,6 +358,7 @@ impl WorkingUser {
                 binding_layouts: &self.binding_layouts,
                 action_states: &mut self.action_states,
                 compound_action_states: &mut self.compound_action_states,
+                window: self.window,
                 callbacks,
                 actions,
                 interaction_profile_id: *interaction_profile_index,
@@ -317,6 +390,7 @@ impl WorkingUser {
             binding_layouts: &self.binding_layouts,
             action_states: &mut self.action_states,
             compound_action_states: &mut self.compound_action_states,
+            window: self.window,
             callbacks,
             actions,
             interaction_profile_id: interaction_profile.ty.id,
@@ -330,6 +404,11 @@ impl WorkingUser {
 
         sorted_action_sets.sort_by(|left, right| left.handle.cmp(&right.handle).reverse());
 
+        //Modifiers may already be held, which decides the bindings enabling the actions activates
+        attached_binding_layout
+            .binding_layout
+            .seed_chords(interaction_profile);
+
         for action_set in sorted_action_sets {
             for action in action_set.baked_actions.get().unwrap() {
                 attached_binding_layout.binding_layout.handle_action_enable(
@@ -340,6 +419,42 @@ impl WorkingUser {
                 );
             }
         }
+
+        //Inputs may already be held when the layout replaces another one mid-session
+        attached_binding_layout
+            .binding_layout
+            .activate_held_inputs(interaction_profile, &mut wui);
+    }
+
+    /// Detaches the interaction profile's binding layout, releasing every action it was holding
+    pub(crate) fn disable_binding_layout(
+        &mut self,
+        interaction_profile_id: InteractionProfilePath,
+        callbacks: &mut [Box<dyn ActionListener>],
+        actions: &HashMap<u64, Arc<Action>>,
+        active_action_sets: &HashSet<u64>,
+    ) {
+        let mut attached_binding_layout = match self.binding_layouts.remove(&interaction_profile_id)
+        {
+            Some(attached_binding_layout) => attached_binding_layout.into_inner(),
+            None => return,
+        };
+
+        let mut wui = WorkingUserInterface {
+            binding_layout_action_states: &mut attached_binding_layout.action_states,
+            binding_layouts: &self.binding_layouts,
+            action_states: &mut self.action_states,
+            compound_action_states: &mut self.compound_action_states,
+            window: self.window,
+            callbacks,
+            actions,
+            interaction_profile_id,
+            active_action_sets,
+        };
+
+        attached_binding_layout
+            .binding_layout
+            .interrupt_all(&mut wui);
     }
 }
 
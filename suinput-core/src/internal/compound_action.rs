@@ -167,7 +167,7 @@ impl CompoundActionState for CompoundAxis1dState {
         }
 
         let new_state = (self.positive - self.negative + self.parent).clamp(-1., 1.);
-        if new_state.abs() != self.state.abs() {
+        if new_state != self.state {
             self.state = new_state;
             Some(ActionEventEnum::Axis1d { state: new_state })
         } else {
@@ -1 +1,2 @@
 pub mod serial_device_type;
+pub mod serial_interaction_profile_type;
@@ -1,9 +1,17 @@
-use suinput_types::event::PathFormatError;
+use std::ffi::OsStr;
+
+use log::{info, warn};
+
+use suinput_types::SuPath;
 
 use crate::internal::types::HashMap;
 use crate::internal::{
-    device_type::DeviceType, input_component::InputComponentType, paths::PathManager,
+    device_type::DeviceType,
+    input_component::InputComponentType,
+    output_component::OutputComponentType,
+    paths::{InputPath, OutputPath, PathManager},
 };
+use crate::runtime::{RuntimeConfig, RuntimeConfigError};
 
 #[derive(Debug, knuffel::Decode)]
 pub struct Device {
@@ -11,19 +19,101 @@ pub struct Device {
     pub vendor: String,
     #[knuffel(property)]
     pub name: String,
+    #[knuffel(property)]
+    pub variant: Option<String>,
+    /// device/<vendor>/<name>, components are inherited from the parent and may be redeclared
+    #[knuffel(property)]
+    pub parent: Option<String>,
+
+    #[knuffel(children)]
+    pub sources: Vec<Source>,
+}
+
+impl Device {
+    /// <vendor>/<name> or <vendor>/<name>/<variant>
+    pub fn key(&self) -> String {
+        match &self.variant {
+            Some(variant) => format!("{}/{}/{}", self.vendor, self.name, variant),
+            None => format!("{}/{}", self.vendor, self.name),
+        }
+    }
+}
 
-    #[knuffel(children(name = "identifier"))]
-    pub identifiers: Vec<Identifier>,
+//Variant names are the KDL node names
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, knuffel::Decode)]
+pub enum Source {
+    Identifier(Generic),
+    Generic(Generic),
+    Source(GenericSource),
+    Dpad(Dpad),
+    Motion(Motion),
 }
 
 #[derive(Debug, knuffel::Decode)]
-pub struct Identifier {
+pub struct Generic {
     #[knuffel(argument)]
     pub name: String,
     #[knuffel(children)]
     pub components: Vec<Component>,
 }
 
+/// Components declared as `input-component "<type>" "<name>"` or just `input-component "<name>"` when the type
+/// can be inferred from the name
+#[derive(Debug, knuffel::Decode)]
+pub struct GenericSource {
+    #[knuffel(argument)]
+    pub name: String,
+    #[knuffel(children(name = "input-component"))]
+    pub components: Vec<InputComponent>,
+}
+
+#[derive(Debug, knuffel::Decode)]
+pub struct InputComponent {
+    #[knuffel(argument)]
+    pub first: String,
+    #[knuffel(argument)]
+    pub second: Option<String>,
+}
+
+/// Expands into a `click` button for each direction
+#[derive(Debug, knuffel::Decode)]
+pub struct Dpad {
+    #[knuffel(argument)]
+    pub name: String,
+    #[knuffel(children)]
+    pub directions: Vec<DpadDirection>,
+}
+
+//Variant names are the KDL node names
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, knuffel::Decode)]
+pub enum DpadDirection {
+    DpadUp(#[knuffel(argument)] String),
+    DpadLeft(#[knuffel(argument)] String),
+    DpadDown(#[knuffel(argument)] String),
+    DpadRight(#[knuffel(argument)] String),
+}
+
+#[derive(Debug, knuffel::Decode)]
+pub struct Motion {
+    #[knuffel(argument)]
+    pub name: String,
+    #[knuffel(children)]
+    pub components: Vec<MotionComponent>,
+}
+
+//Variant names are the KDL node names
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, knuffel::Decode)]
+pub enum MotionComponent {
+    MotionGyro(
+        #[knuffel(argument)] String,
+        #[knuffel(property(name = "calibrated"))] bool,
+    ),
+    MotionAccel(#[knuffel(argument)] String),
+}
+
 #[derive(Debug, knuffel::Decode)]
 pub enum Component {
     Button(#[knuffel(argument)] String),
@@ -40,7 +130,9 @@ pub enum Component {
         #[knuffel(argument)] String,
         #[knuffel(property(name = "max_points"))] u32,
         #[knuffel(property(name = "pressure"))] bool,
+        #[knuffel(property(name = "diameter"), default)] bool,
     ),
+    Cursor(#[knuffel(argument)] String),
     Led(#[knuffel(argument)] String),
     PlayerNumber(
         #[knuffel(argument)] String,
@@ -53,58 +145,418 @@ pub enum Component {
 
 pub static DEVICES: &str = include_str!("devices.kdl");
 
+/// Loads the built-in devices followed by the user's device files
+///
+/// A device declared again with the same vendor and name replaces the earlier declaration
+pub fn load(config: &RuntimeConfig) -> Result<Vec<Device>, RuntimeConfigError> {
+    let mut devices = knuffel::parse::<Vec<Device>>("devices.kdl", DEVICES)
+        .unwrap_or_else(|err| panic!("{:?}", miette::Report::new(err)));
+    let mut sources = vec![None; devices.len()];
+
+    let mut files = Vec::new();
+    for directory in &config.device_directories {
+        let mut directory_files = std::fs::read_dir(directory)
+            .map_err(|err| RuntimeConfigError::Io(directory.clone(), err))?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| RuntimeConfigError::Io(directory.clone(), err))?;
+        directory_files.retain(|path| path.extension().and_then(OsStr::to_str) == Some("kdl"));
+        directory_files.sort();
+        files.extend(directory_files);
+    }
+    files.extend(config.device_files.iter().cloned());
+
+    for file in files {
+        let text = std::fs::read_to_string(&file)
+            .map_err(|err| RuntimeConfigError::Io(file.clone(), err))?;
+        let file_name = file.to_string_lossy();
+
+        for device in knuffel::parse::<Vec<Device>>(&file_name, &text)? {
+            let key = device.key();
+            match declare(&mut devices, &mut sources, device, &file_name) {
+                Declaration::New => {}
+                Declaration::OverridesBuiltin => {
+                    info!("Device {} overridden by {}", key, file_name)
+                }
+                Declaration::Redeclared(old_file) => warn!(
+                    "Device {} declared in both {} and {}, using the latter",
+                    key, old_file, file_name
+                ),
+            }
+        }
+    }
+
+    Ok(devices)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Declaration {
+    New,
+    OverridesBuiltin,
+    Redeclared(String),
+}
+
+/// Adds the device or replaces an earlier declaration with the same key, `sources` holds the file of each device
+fn declare(
+    devices: &mut Vec<Device>,
+    sources: &mut Vec<Option<String>>,
+    device: Device,
+    file_name: &str,
+) -> Declaration {
+    match devices.iter().position(|old| old.key() == device.key()) {
+        Some(idx) => {
+            devices[idx] = device;
+            match sources[idx].replace(file_name.to_string()) {
+                Some(old_file) => Declaration::Redeclared(old_file),
+                None => Declaration::OverridesBuiltin,
+            }
+        }
+        None => {
+            devices.push(device);
+            sources.push(Some(file_name.to_string()));
+            Declaration::New
+        }
+    }
+}
+
+pub fn deserialize(
+    paths: &PathManager,
+    devices: &[Device],
+) -> Result<Vec<DeviceType>, RuntimeConfigError> {
+    let devices_by_key = devices
+        .iter()
+        .map(|device| (device.key(), device))
+        .collect::<HashMap<_, _>>();
+
+    let mut resolved = HashMap::new();
+
+    devices
+        .iter()
+        .map(|device| {
+            let (input_components, output_components) = resolve_components(
+                device,
+                &devices_by_key,
+                paths,
+                &mut resolved,
+                &mut Vec::new(),
+            )?;
+
+            let id = get_path(paths, &format!("/devices/{}", device.key()))?;
+
+            Ok(DeviceType::new(id, input_components, output_components))
+        })
+        .collect()
+}
+
+type Components = (
+    HashMap<InputPath, InputComponentType>,
+    HashMap<OutputPath, OutputComponentType>,
+);
+
+fn resolve_components(
+    device: &Device,
+    devices: &HashMap<String, &Device>,
+    paths: &PathManager,
+    resolved: &mut HashMap<String, Components>,
+    visiting: &mut Vec<String>,
+) -> Result<Components, RuntimeConfigError> {
+    let key = device.key();
+
+    if let Some(components) = resolved.get(&key) {
+        return Ok(components.clone());
+    }
+
+    if visiting.contains(&key) {
+        return Err(RuntimeConfigError::InheritanceCycle(key));
+    }
+    visiting.push(key.clone());
+
+    let (mut input_components, mut output_components) = match &device.parent {
+        Some(parent) => {
+            let parent_key = parent
+                .trim_start_matches("/devices/")
+                .trim_start_matches("device/");
+
+            let parent_device = devices
+                .get(parent_key)
+                .ok_or_else(|| RuntimeConfigError::UnknownParent(key.clone(), parent.clone()))?;
+
+            resolve_components(parent_device, devices, paths, resolved, visiting)?
+        }
+        None => (HashMap::new(), HashMap::new()),
+    };
+
+    for source in &device.sources {
+        for (name, ty) in source_components(source) {
+            input_components.insert(get_path(paths, &format!("/input/{}", name))?, ty);
+        }
+
+        for (name, ty) in source_output_components(source) {
+            output_components.insert(get_path(paths, &format!("/output/{}", name))?, ty);
+        }
+    }
+
+    visiting.pop();
+    resolved.insert(key, (input_components.clone(), output_components.clone()));
+
+    Ok((input_components, output_components))
+}
+
 #[rustfmt::skip]
-pub fn deserialize(paths: &PathManager) -> Vec<DeviceType> {
-    let devices = knuffel::parse::<Vec<Device>>(".kdl", DEVICES).unwrap();
-    
-    devices.iter().map(|device| {
-        let id = paths.get_path(&format!("/devices/{}/{}", device.vendor, device.name)).unwrap();
-
-        let input_components = device.identifiers.iter().flat_map(|identifier| {
-            identifier.components.iter().filter_map(|component| {
+/// Returns each input component of the source as `<source>/<component>`
+fn source_components(source: &Source) -> Vec<(String, InputComponentType)> {
+    match source {
+        Source::Identifier(generic) | Source::Generic(generic) => {
+            generic.components.iter().filter_map(|component| {
                 Some(match component {
-                    Component::Button(name) => (InputComponentType::Button, name),
-                    Component::Trigger(name) => (InputComponentType::Trigger, name),
-                    Component::Joystick(name) => (InputComponentType::Joystick, name),
-                    Component::Move2d(name) => (InputComponentType::Move2D, name),
-                    Component::Gyro(name, cal) => (InputComponentType::Gyro(*cal), name),
-                    Component::Accel(name) => (InputComponentType::Accel, name),
+                    Component::Button(name) => (name, InputComponentType::Button),
+                    Component::Trigger(name) => (name, InputComponentType::Trigger),
+                    Component::Joystick(name) => (name, InputComponentType::Joystick),
+                    Component::Move2d(name) => (name, InputComponentType::Move2D),
+                    Component::Gyro(name, cal) => (name, InputComponentType::Gyro(*cal)),
+                    Component::Accel(name) => (name, InputComponentType::Accel),
                     Component::AdaptiveTrigger(_) => return None,
-                    Component::Touchpad(_, _, _) => return None,
+                    Component::Touchpad(name, max_points, pressure, _) => (
+                        name,
+                        InputComponentType::Touchpad { max_points: *max_points, pressure: *pressure },
+                    ),
+                    Component::Cursor(name) => (name, InputComponentType::Cursor),
                     Component::Led(_) => return None,
                     Component::PlayerNumber(_, _, _) => return None,
                     Component::HdRumble(_) => return None,
                     Component::Rumble(_) => return None,
                 })
             })
-            .map(|(ty, name)| {
-                Ok((paths.get_path(&format!("/input/{}/{}", identifier.name, name))?, ty))
+            .map(|(name, ty)| (format!("{}/{}", generic.name, name), ty))
+            .collect()
+        }
+        Source::Source(source) => {
+            source.components.iter().filter_map(|component| {
+                let (ty, name) = match &component.second {
+                    Some(name) => (component.first.as_str(), name.as_str()),
+                    None => (component.first.as_str(), component.first.as_str()),
+                };
+
+                let ty = match ty {
+                    "button" | "click" | "touch" => InputComponentType::Button,
+                    "trigger" | "value" => InputComponentType::Trigger,
+                    "joystick" | "position" => InputComponentType::Joystick,
+                    "move2d" => InputComponentType::Move2D,
+                    _ => {
+                        warn!("Unsupported input component {:?} on source {}", ty, source.name);
+                        return None;
+                    }
+                };
+
+                Some((format!("{}/{}", source.name, name), ty))
+            })
+            .collect()
+        }
+        Source::Dpad(dpad) => {
+            //<dpad>_<direction>/click to match the paths of individually declared buttons
+            dpad.directions.iter().map(|direction| {
+                let (DpadDirection::DpadUp(name)
+                | DpadDirection::DpadLeft(name)
+                | DpadDirection::DpadDown(name)
+                | DpadDirection::DpadRight(name)) = direction;
+
+                (format!("{}_{}/click", dpad.name, name), InputComponentType::Button)
             })
+            .collect()
         }
-    ).collect::<Result<HashMap<_, _>, PathFormatError>>().unwrap();
-    
-    let mut gyro = None;
-    let mut accel = None;
-
-    for (path, ty) in &input_components {
-        match ty {
-            InputComponentType::Gyro(_) => gyro = Some(*path),
-            InputComponentType::Accel => accel = Some(*path),
-            _ => ()
+        Source::Motion(motion) => {
+            motion.components.iter().map(|component| match component {
+                MotionComponent::MotionGyro(name, cal) => (format!("{}/{}", motion.name, name), InputComponentType::Gyro(*cal)),
+                MotionComponent::MotionAccel(name) => (format!("{}/{}", motion.name, name), InputComponentType::Accel),
+            })
+            .collect()
         }
     }
+}
 
-    DeviceType {
-        id,
-        input_components,
-        gyro,
-        accel,
+#[rustfmt::skip]
+/// Returns each output component of the source as `<source>/<component>`
+fn source_output_components(source: &Source) -> Vec<(String, OutputComponentType)> {
+    match source {
+        Source::Identifier(generic) | Source::Generic(generic) => {
+            generic.components.iter().filter_map(|component| {
+                Some(match component {
+                    Component::AdaptiveTrigger(name) => (name, OutputComponentType::AdaptiveTrigger),
+                    Component::Led(name) => (name, OutputComponentType::Led),
+                    Component::PlayerNumber(name, min, max) => (name, OutputComponentType::PlayerNumber { min: *min, max: *max }),
+                    Component::HdRumble(name) => (name, OutputComponentType::HdRumble),
+                    Component::Rumble(name) => (name, OutputComponentType::Rumble),
+                    _ => return None,
+                })
+            })
+            .map(|(name, ty)| (format!("{}/{}", generic.name, name), ty))
+            .collect()
+        }
+        _ => Vec::new(),
     }
-    }).collect()
+}
+
+fn get_path(paths: &PathManager, path_string: &str) -> Result<SuPath, RuntimeConfigError> {
+    paths
+        .get_path(path_string)
+        .map_err(|_| RuntimeConfigError::BadPath(path_string.to_owned()))
+}
+
+#[cfg(test)]
+fn parse_test_devices(text: &str) -> (PathManager, Result<Vec<DeviceType>, RuntimeConfigError>) {
+    let paths = PathManager::new();
+    let devices = knuffel::parse::<Vec<Device>>("test.kdl", text).unwrap();
+    let device_types = deserialize(&paths, &devices);
+    (paths, device_types)
 }
 
 #[test]
 fn test_device_types() {
     let paths = PathManager::new();
-    println!("{} device types", deserialize(&paths).len())
+    let devices = load(&RuntimeConfig::default()).unwrap();
+    let device_types = deserialize(&paths, &devices).unwrap();
+
+    let dualsense = device_types
+        .iter()
+        .find(|device_type| device_type.id == paths.get_path("/devices/sony/dualsense").unwrap())
+        .unwrap();
+    assert!(dualsense
+        .input_components
+        .contains_key(&paths.get_path("/input/dpad_up/click").unwrap()));
+}
+
+#[test]
+fn test_parent_inheritance() {
+    let (paths, device_types) = parse_test_devices(
+        r#"
+        device vendor="test" name="base" {
+            generic "a" { button "click"; }
+            generic "b" { button "click"; }
+        }
+        device vendor="test" name="child" parent="/devices/test/base" {
+            generic "b" { trigger "value"; }
+            generic "c" { joystick "position"; }
+        }
+        "#,
+    );
+    let device_types = device_types.unwrap();
+
+    let child = &device_types[1];
+    assert_eq!(child.id, paths.get_path("/devices/test/child").unwrap());
+    assert!(matches!(
+        child.input_components[&paths.get_path("/input/a/click").unwrap()],
+        InputComponentType::Button
+    ));
+    assert!(matches!(
+        child.input_components[&paths.get_path("/input/b/value").unwrap()],
+        InputComponentType::Trigger
+    ));
+    assert!(matches!(
+        child.input_components[&paths.get_path("/input/b/click").unwrap()],
+        InputComponentType::Button
+    ));
+    assert_eq!(child.input_components.len(), 4);
+    assert_eq!(device_types[0].input_components.len(), 2);
+}
+
+#[test]
+fn test_dpad_expansion() {
+    let (paths, device_types) = parse_test_devices(
+        r#"
+        device vendor="test" name="pad" {
+            dpad "dpad" {
+                dpad-up "up"
+                dpad-left "left"
+                dpad-right "right"
+                dpad-down "down"
+            }
+        }
+        "#,
+    );
+    let pad = &device_types.unwrap()[0];
+
+    for direction in ["up", "left", "right", "down"] {
+        let path = paths
+            .get_path(&format!("/input/dpad_{direction}/click"))
+            .unwrap();
+        assert!(matches!(
+            pad.input_components[&path],
+            InputComponentType::Button
+        ));
+    }
+    assert_eq!(pad.input_components.len(), 4);
+}
+
+#[test]
+fn test_parent_cycle() {
+    let (_, device_types) = parse_test_devices(
+        r#"
+        device vendor="test" name="a" parent="/devices/test/b"
+        device vendor="test" name="b" parent="/devices/test/a"
+        "#,
+    );
+
+    assert!(matches!(
+        device_types,
+        Err(RuntimeConfigError::InheritanceCycle(_))
+    ));
+}
+
+#[test]
+fn test_user_file_overrides_builtin() {
+    let directory = std::env::temp_dir().join(format!("suinput-test-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+
+    let first = directory.join("first.kdl");
+    let second = directory.join("second.kdl");
+    std::fs::write(
+        &first,
+        r#"device vendor="standard" name="system_cursor" { generic "first" { button "click"; } }"#,
+    )
+    .unwrap();
+    std::fs::write(
+        &second,
+        r#"device vendor="standard" name="system_cursor" { generic "second" { button "click"; } }"#,
+    )
+    .unwrap();
+
+    let devices = load(&RuntimeConfig {
+        device_files: vec![first.clone(), second.clone()],
+        device_directories: vec![],
+    })
+    .unwrap();
+    std::fs::remove_dir_all(&directory).unwrap();
+
+    let system_cursor = devices
+        .iter()
+        .filter(|device| device.key() == "standard/system_cursor")
+        .collect::<Vec<_>>();
+    assert_eq!(system_cursor.len(), 1);
+    assert!(
+        matches!(&system_cursor[0].sources[..], [Source::Generic(generic)] if generic.name == "second")
+    );
+
+    let mut devices = knuffel::parse::<Vec<Device>>("devices.kdl", DEVICES).unwrap();
+    let mut sources = vec![None; devices.len()];
+    let parse = |text: &str| {
+        knuffel::parse::<Vec<Device>>("test.kdl", text)
+            .unwrap()
+            .remove(0)
+    };
+
+    let device = parse(r#"device vendor="standard" name="system_cursor""#);
+    assert_eq!(
+        declare(&mut devices, &mut sources, device, "first.kdl"),
+        Declaration::OverridesBuiltin
+    );
+    let device = parse(r#"device vendor="standard" name="system_cursor""#);
+    assert_eq!(
+        declare(&mut devices, &mut sources, device, "second.kdl"),
+        Declaration::Redeclared("first.kdl".into())
+    );
+    let device = parse(r#"device vendor="test" name="new""#);
+    assert_eq!(
+        declare(&mut devices, &mut sources, device, "second.kdl"),
+        Declaration::New
+    );
 }
@@ -0,0 +1,138 @@
+use suinput_types::SuPath;
+
+use crate::internal::types::HashMap;
+use crate::internal::{
+    device_types::DeviceTypes, interaction_profile_type::InteractionProfileType, paths::PathManager,
+};
+use crate::runtime::RuntimeConfigError;
+
+#[derive(Debug, knuffel::Decode)]
+pub struct InteractionProfile {
+    #[knuffel(property)]
+    pub vendor: String,
+    #[knuffel(property)]
+    pub name: String,
+
+    #[knuffel(children(name = "user"))]
+    pub users: Vec<User>,
+}
+
+#[derive(Debug, knuffel::Decode)]
+pub struct User {
+    #[knuffel(argument)]
+    pub path: String,
+    #[knuffel(property)]
+    pub device: String,
+
+    #[knuffel(children(name = "alias"))]
+    pub aliases: Vec<Alias>,
+}
+
+/// Lets bindings refer to an input component of the device by another name
+#[derive(Debug, knuffel::Decode)]
+pub struct Alias {
+    #[knuffel(argument)]
+    pub from: String,
+    #[knuffel(argument)]
+    pub to: String,
+}
+
+pub static INTERACTION_PROFILES: &str = include_str!("interaction_profiles.kdl");
+
+pub fn deserialize(
+    paths: &PathManager,
+    device_types: &DeviceTypes,
+) -> Result<Vec<InteractionProfileType>, RuntimeConfigError> {
+    let interaction_profiles = knuffel::parse::<Vec<InteractionProfile>>(
+        "interaction_profiles.kdl",
+        INTERACTION_PROFILES,
+    )?;
+
+    interaction_profiles
+        .iter()
+        .map(|interaction_profile| {
+            let id = get_path(
+                paths,
+                &format!(
+                    "/interaction_profiles/{}/{}",
+                    interaction_profile.vendor, interaction_profile.name
+                ),
+            )?;
+
+            let user2device = interaction_profile
+                .users
+                .iter()
+                .map(|user| {
+                    let device_type = device_types
+                        .get(get_path(paths, &user.device)?)
+                        .ok_or_else(|| {
+                            RuntimeConfigError::UnknownDeviceType(
+                                interaction_profile.name.clone(),
+                                user.device.clone(),
+                            )
+                        })?;
+                    Ok((get_path(paths, &user.path)?, device_type.clone()))
+                })
+                .collect::<Result<HashMap<_, _>, RuntimeConfigError>>()?;
+
+            let aliases = interaction_profile
+                .users
+                .iter()
+                .flat_map(|user| {
+                    user.aliases.iter().map(|alias| {
+                        Ok((
+                            (get_path(paths, &user.path)?, get_path(paths, &alias.from)?),
+                            get_path(paths, &alias.to)?,
+                        ))
+                    })
+                })
+                .collect::<Result<HashMap<_, _>, RuntimeConfigError>>()?;
+
+            Ok(InteractionProfileType::new(id, user2device, aliases))
+        })
+        .collect()
+}
+
+fn get_path(paths: &PathManager, path_string: &str) -> Result<SuPath, RuntimeConfigError> {
+    paths
+        .get_path(path_string)
+        .map_err(|_| RuntimeConfigError::BadPath(path_string.to_owned()))
+}
+
+#[test]
+fn test_interaction_profiles() {
+    use crate::internal::paths::CommonPaths;
+    use suinput_types::keyboard::KeyboardPaths;
+
+    let paths = PathManager::new();
+    let common_paths = CommonPaths::new(|str| paths.get_path(str).unwrap());
+    let keyboard_paths = KeyboardPaths::new(|str| paths.get_path(str).unwrap());
+    let device_types =
+        DeviceTypes::new(&common_paths, &keyboard_paths, &paths, &Default::default()).unwrap();
+    let interaction_profiles = deserialize(&paths, &device_types).unwrap();
+
+    let get = |path: &str| {
+        let id = paths.get_path(path).unwrap();
+        interaction_profiles
+            .iter()
+            .find(|interaction_profile| interaction_profile.id == id)
+            .unwrap_or_else(|| panic!("Missing interaction profile {path}"))
+    };
+
+    let desktop = get("/interaction_profiles/standard/desktop");
+    assert_eq!(desktop.user2device.len(), 3);
+    assert_eq!(
+        desktop.user2device[&paths.get_path("/user/desktop/keyboard").unwrap()].id,
+        paths.get_path("/devices/standard/hid_keyboard").unwrap()
+    );
+
+    let dualsense = get("/interaction_profiles/sony/dualsense");
+    assert!(dualsense.aliases.is_empty());
+
+    let xbox_360 = get("/interaction_profiles/microsoft/xbox_360");
+    let gamepad = paths.get_path("/user/gamepad").unwrap();
+    assert_eq!(
+        xbox_360.aliases[&(gamepad, paths.get_path("/input/view/click").unwrap())],
+        paths.get_path("/input/back/click").unwrap()
+    );
+}
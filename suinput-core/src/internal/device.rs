@@ -7,7 +7,9 @@ use suinput_types::{
 };
 
 use super::{
-    device_type::DeviceType, input_component::InputComponentData, motion::GamepadMotion,
+    device_type::DeviceType,
+    input_component::{InputComponentData, InputComponentState},
+    motion::GamepadMotion,
     paths::InputPath,
 };
 use crate::internal::types::HashMap;
@@ -35,18 +37,17 @@ impl DeviceState {
         time: Instant,
         batch: &HashMap<SuPath, InputComponentEvent>,
     ) -> Result<(), ()> {
-        if self.ty.gyro.is_some() && self.ty.accel.is_some() {
-            let gyro_path = self.ty.gyro.unwrap();
-            let accel_path = self.ty.accel.unwrap();
-
-            let gyro: Vector3<f32> = match batch.get(&gyro_path).unwrap() {
-                InputComponentEvent::Gyro(gyro) => (*gyro).into(),
-                _ => return Ok(()),
+        if let (Some(gyro_path), Some(accel_path)) = (self.ty.gyro, self.ty.accel) {
+            let gyro: Vector3<f32> = match batch.get(&gyro_path) {
+                Some(InputComponentEvent::Gyro(gyro)) => (*gyro).into(),
+                Some(_) => return Err(()),
+                None => return Ok(()),
             };
 
-            let accel: Vector3<f32> = match batch.get(&accel_path).unwrap() {
-                InputComponentEvent::Accel(accel) => (*accel).into(),
-                _ => return Ok(()),
+            let accel: Vector3<f32> = match batch.get(&accel_path) {
+                Some(InputComponentEvent::Accel(accel)) => (*accel).into(),
+                Some(_) => return Err(()),
+                None => return Ok(()),
             };
 
             let delta_time = if let Some(last_time) = &mut self.last_update {
@@ -80,17 +81,22 @@ impl DeviceState {
         //     }
         // }
 
-        // self.input_component_states.insert(
-        //     event.path,
-        //     InputComponentData {
-        //         last_update_time: Instant::now(),
-        //         state: match event.data {
-        //             InputComponentEvent::Button(pressed) => InputComponentState::Button(pressed),
-        //             InputComponentEvent::Cursor(cursor) => InputComponentState::Cursor(cursor),
-        //             _ => InputComponentState::NonApplicable,
-        //         },
-        //     },
-        // );
+        self.input_component_states.insert(
+            event.path,
+            InputComponentData {
+                last_update_time: Instant::now(),
+                state: match event.data {
+                    InputComponentEvent::Button(pressed) => InputComponentState::Button(pressed),
+                    InputComponentEvent::Trigger(value) => InputComponentState::Trigger(value),
+                    InputComponentEvent::Joystick(value) => {
+                        InputComponentState::Joystick(value.into())
+                    }
+                    InputComponentEvent::Touchpad(state) => InputComponentState::Touchpad(state),
+                    InputComponentEvent::Cursor(cursor) => InputComponentState::Cursor(cursor),
+                    _ => InputComponentState::NonApplicable,
+                },
+            },
+        );
 
         Some(event)
     }
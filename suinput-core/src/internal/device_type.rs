@@ -9,13 +9,15 @@ use crate::internal::types::HashMap;
 
 use super::{
     input_component::InputComponentType,
-    paths::{DevicePath, InputPath},
+    output_component::OutputComponentType,
+    paths::{DevicePath, InputPath, OutputPath},
 };
 
 #[derive(Debug, Clone)]
 pub struct DeviceType {
     pub id: DevicePath,
     pub input_components: HashMap<SuPath, InputComponentType>,
+    pub output_components: HashMap<OutputPath, OutputComponentType>,
 
     //We assume that a device will only have one of each motion sensor
     //This can be changed in future
@@ -24,12 +26,38 @@ pub struct DeviceType {
 }
 
 impl DeviceType {
+    pub fn new(
+        id: DevicePath,
+        input_components: HashMap<InputPath, InputComponentType>,
+        output_components: HashMap<OutputPath, OutputComponentType>,
+    ) -> Self {
+        let mut gyro = None;
+        let mut accel = None;
+
+        for (path, ty) in &input_components {
+            match ty {
+                InputComponentType::Gyro(_) => gyro = Some(*path),
+                InputComponentType::Accel => accel = Some(*path),
+                _ => (),
+            }
+        }
+
+        Self {
+            id,
+            input_components,
+            output_components,
+            gyro,
+            accel,
+        }
+    }
+
     pub fn create_keyboard(paths: &CommonPaths, keyboard_paths: &KeyboardPaths) -> Self {
         Self {
             id: paths.keyboard,
             input_components: HIDScanCode::iter()
                 .map(|scan_code| (keyboard_paths.get(scan_code), InputComponentType::Button))
                 .collect::<HashMap<SuPath, InputComponentType>>(),
+            output_components: HashMap::new(),
             gyro: None,
             accel: None,
         }
@@ -1,12 +1,20 @@
 use std::sync::Arc;
 
-use dashmap::{mapref::one::Ref, DashMap};
+use dashmap::{
+    mapref::{entry::Entry, one::Ref},
+    DashMap,
+};
 use suinput_types::keyboard::KeyboardPaths;
 
 use super::{
     config::serial_device_type,
     device_type::DeviceType,
     paths::{CommonPaths, DevicePath, PathManager},
+    types::HashMap,
+};
+use crate::{
+    driver_interface::{DeviceTypeInfo, RuntimeInterfaceError},
+    runtime::{RuntimeConfig, RuntimeConfigError},
 };
 
 pub struct DeviceTypes {
@@ -18,17 +26,88 @@ impl DeviceTypes {
         common_paths: &CommonPaths,
         keyboard_paths: &KeyboardPaths,
         paths: &PathManager,
-    ) -> Self {
-        Self {
+        config: &RuntimeConfig,
+    ) -> Result<Self, RuntimeConfigError> {
+        let devices = serial_device_type::load(config)?;
+
+        Ok(Self {
             cache: [DeviceType::create_keyboard(common_paths, keyboard_paths)]
                 .into_iter()
-                .chain(serial_device_type::deserialize(paths))
+                .chain(serial_device_type::deserialize(paths, &devices)?)
                 .map(|device_type| (device_type.id, Arc::new(device_type)))
                 .collect(),
-        }
+        })
     }
 
     pub fn get(&self, path: DevicePath) -> Option<Ref<'_, DevicePath, Arc<DeviceType>>> {
         self.cache.get(&path)
     }
+
+    pub fn register(
+        &self,
+        device_type: DeviceTypeInfo,
+        paths: &PathManager,
+    ) -> Result<(), RuntimeInterfaceError> {
+        let id_string = paths
+            .get_path_string(device_type.id)
+            .ok_or(RuntimeInterfaceError::InvalidPathHandle(device_type.id))?;
+
+        if !id_string.starts_with("/devices/") {
+            return Err(RuntimeInterfaceError::BadDeviceTypePath(id_string));
+        }
+
+        let (mut input_components, mut output_components) = match device_type.parent {
+            Some(parent) => match self.get(parent) {
+                Some(parent) => (
+                    parent.input_components.clone(),
+                    parent.output_components.clone(),
+                ),
+                None => {
+                    return Err(paths.get_path_string(parent).map_or(
+                        RuntimeInterfaceError::InvalidPathHandle(parent),
+                        RuntimeInterfaceError::UnknownDeviceType,
+                    ))
+                }
+            },
+            None => (HashMap::new(), HashMap::new()),
+        };
+
+        for (path, ty) in device_type.input_components {
+            let path_string = paths
+                .get_path_string(path)
+                .ok_or(RuntimeInterfaceError::InvalidPathHandle(path))?;
+
+            if !path_string.starts_with("/input/") {
+                return Err(RuntimeInterfaceError::BadComponentPath(path_string));
+            }
+
+            input_components.insert(path, ty);
+        }
+
+        for (path, ty) in device_type.output_components {
+            let path_string = paths
+                .get_path_string(path)
+                .ok_or(RuntimeInterfaceError::InvalidPathHandle(path))?;
+
+            if !path_string.starts_with("/output/") {
+                return Err(RuntimeInterfaceError::BadComponentPath(path_string));
+            }
+
+            output_components.insert(path, ty);
+        }
+
+        match self.cache.entry(device_type.id) {
+            Entry::Occupied(_) => Err(RuntimeInterfaceError::DeviceTypeAlreadyRegistered(
+                id_string,
+            )),
+            Entry::Vacant(entry) => {
+                entry.insert(Arc::new(DeviceType::new(
+                    device_type.id,
+                    input_components,
+                    output_components,
+                )));
+                Ok(())
+            }
+        }
+    }
 }
@@ -2,9 +2,10 @@ use std::{cell::RefCell, sync::Arc, time::Instant};
 
 use flume::Receiver;
 use hashbrown::{HashMap, HashSet};
+use log::warn;
 use suinput_types::{
     action::{ActionEvent, ActionListener, ActionStateEnum},
-    event::InputEvent,
+    event::{InputComponentEvent, InputEvent, OutputComponentEvent, OutputEvent},
 };
 use thunderdome::Index;
 
@@ -17,20 +18,34 @@ use super::{
     binding::working_user::{AttachedBindingLayout, WorkingUser},
     device::DeviceState,
     device_type::DeviceType,
+    input_component::InputComponentState,
     interaction_profile::InteractionProfileState,
     parallel_arena::ParallelArena,
-    paths::InteractionProfilePath,
+    paths::{InputPath, InteractionProfilePath, UserPath},
+    worker_thread::WorkerThreadEvent,
 };
 
 pub enum Runtime2SessionEvent {
-    RegisterDevice { idx: Index, ty: Arc<DeviceType> },
-    DisconnectDevice { idx: Index },
+    RegisterDevice {
+        idx: Index,
+        ty: Arc<DeviceType>,
+        driver: usize,
+    },
+    DisconnectDevice {
+        idx: Index,
+    },
     Input(InputEvent),
     BatchInput(BatchInputUpdate),
 }
 
 pub enum SessionActionEvent {
-    Unstick { action: u64 },
+    Unstick {
+        action: u64,
+    },
+    Output {
+        action: u64,
+        event: OutputComponentEvent,
+    },
 }
 
 pub struct InnerSession {
@@ -42,35 +57,22 @@ pub struct InnerSession {
 
     pub default_interaction_profiles: HashMap<InteractionProfilePath, InteractionProfileState>,
     pub device_states: ParallelArena<(DeviceState, InteractionProfilePath)>,
+    //Used to ask drivers for the state of components the session hasn't seen change yet
+    pub device_drivers: HashMap<Index, usize>,
 }
 
 impl InnerSession {
     pub fn new(runtime: &Arc<Runtime>, action_sets: &HashMap<u64, Arc<ActionSet>>) -> Self {
-        let mut default_interaction_profiles = HashMap::new();
-        default_interaction_profiles.insert(
-            runtime.common_paths.desktop,
-            InteractionProfileState::new(
-                runtime
-                    .interaction_profile_types
-                    .get(runtime.common_paths.desktop)
-                    .unwrap()
-                    .clone(),
-            ),
-        );
-        default_interaction_profiles.insert(
-            runtime.controller_paths.interaction_profile_dualsense,
-            InteractionProfileState::new(
-                runtime
-                    .interaction_profile_types
-                    .get(runtime.controller_paths.interaction_profile_dualsense)
-                    .unwrap()
-                    .clone(),
-            ),
-        );
+        let default_interaction_profiles = runtime
+            .interaction_profile_types
+            .iter()
+            .map(|profile| (profile.id, InteractionProfileState::new(profile)))
+            .collect();
 
         Self {
             user: WorkingUser::new(action_sets),
             device_states: ParallelArena::new(),
+            device_drivers: HashMap::new(),
             default_interaction_profiles,
             active_action_sets: HashSet::new(),
             old_active_action_sets: HashSet::new(),
@@ -144,10 +146,76 @@ impl InnerSession {
                         }
                     }
                 }
+                SessionActionEvent::Output { action, event } => {
+                    let action = actions.get(&action).unwrap();
+                    let action_set = action.action_set.upgrade().unwrap();
+
+                    if !self.active_action_sets.contains(&action_set.handle) {
+                        continue;
+                    }
+
+                    let time = runtime.get_time(Instant::now());
+
+                    for (interaction_profile_id, user_path, output_path) in
+                        working_user.get_output_bindings(action.handle)
+                    {
+                        let interaction_profile = self
+                            .default_interaction_profiles
+                            .get(&interaction_profile_id)
+                            .unwrap();
+
+                        for device_idx in interaction_profile.get_devices(user_path) {
+                            let (device, _) = self.device_states.get(device_idx).unwrap();
+
+                            match device.ty.output_components.get(&output_path) {
+                                Some(ty) if ty.accepts(&event) => runtime
+                                    .worker_thread_sender
+                                    .send(WorkerThreadEvent::Output(OutputEvent {
+                                        device: device_idx.to_bits(),
+                                        path: output_path,
+                                        time,
+                                        data: event,
+                                    }))
+                                    .unwrap(),
+                                Some(ty) => warn!(
+                                    "Output action {} fired {:?} at a {:?} component",
+                                    action.name, event, ty
+                                ),
+                                None => (),
+                            }
+                        }
+                    }
+                }
             }
         }
 
         for (profile, binding_layout) in user.new_binding_layouts.lock().drain() {
+            //The old binding layout releases everything it was holding before it is replaced or removed
+            working_user.disable_binding_layout(
+                profile,
+                callbacks,
+                actions,
+                &self.active_action_sets,
+            );
+
+            let binding_layout = match binding_layout {
+                Some(binding_layout) => binding_layout,
+                None => continue,
+            };
+
+            //Interaction profiles registered by drivers after this session was created
+            self.default_interaction_profiles
+                .entry(profile)
+                .or_insert_with(|| {
+                    InteractionProfileState::new(
+                        runtime
+                            .interaction_profile_types
+                            .get(profile)
+                            .unwrap()
+                            .clone(),
+                    )
+                });
+
             let mut attached_binding_layout = AttachedBindingLayout::new(binding_layout);
 
             working_user.enable_binding_layout(
@@ -160,12 +228,9 @@ impl InnerSession {
                 action_sets,
             );
 
-            if let Some(_) = working_user
+            working_user
                 .binding_layouts
-                .insert(profile, RefCell::new(attached_binding_layout))
-            {
-                todo!("Disable binding layout")
-            }
+                .insert(profile, RefCell::new(attached_binding_layout));
         }
 
         let mut user_action_states = user.action_states.write();
@@ -200,16 +265,24 @@ impl InnerSession {
 
         while let Ok(event) = events.try_recv() {
             match event {
-                Runtime2SessionEvent::RegisterDevice { idx, ty } => {
+                Runtime2SessionEvent::RegisterDevice { idx, ty, driver } => {
+                    self.device_drivers.insert(idx, driver);
                     self.register_device(&runtime, idx, ty);
                 }
-                Runtime2SessionEvent::DisconnectDevice { idx: _ } => todo!(),
+                Runtime2SessionEvent::DisconnectDevice { idx } => {
+                    self.disconnect_device(&runtime, actions, idx, callbacks);
+                }
                 Runtime2SessionEvent::Input(input) => {
                     self.input_event(actions, input, callbacks);
                 }
-                Runtime2SessionEvent::BatchInput(_) => todo!(),
+                Runtime2SessionEvent::BatchInput(batch) => {
+                    self.batch_input_event(&runtime, actions, batch, callbacks);
+                }
             }
         }
+
+        self.user
+            .on_tick(&self.active_action_sets, actions, callbacks);
     }
 
     fn register_device(
@@ -220,15 +293,13 @@ impl InnerSession {
     ) {
         let ty = device_type.id;
 
-        let interaction_profile_id = if ty == runtime.common_paths.system_cursor
-            || ty == runtime.common_paths.keyboard
-            || ty == runtime.common_paths.mouse
+        let interaction_profile_id = match runtime.interaction_profile_types.get_default_profile(ty)
         {
-            runtime.common_paths.desktop
-        } else if ty == runtime.controller_paths.device_dual_sense {
-            runtime.controller_paths.interaction_profile_dualsense
-        } else {
-            todo!()
+            Some(interaction_profile_id) => interaction_profile_id,
+            None => {
+                warn!("No interaction profile declares device type {:?}", ty);
+                return;
+            }
         };
 
         self.device_states.insert_at(
@@ -236,10 +307,145 @@ impl InnerSession {
             (DeviceState::new(device_type), interaction_profile_id),
         );
 
+        self.default_interaction_profiles
+            .entry(interaction_profile_id)
+            .or_insert_with(|| {
+                InteractionProfileState::new(
+                    runtime
+                        .interaction_profile_types
+                        .get(interaction_profile_id)
+                        .unwrap()
+                        .clone(),
+                )
+            })
+            .device_added(device_idx, ty);
+    }
+
+    fn disconnect_device(
+        &mut self,
+        runtime: &Arc<Runtime>,
+        actions: &HashMap<u64, Arc<Action>>,
+        device_idx: Index,
+        callbacks: &mut [Box<dyn ActionListener>],
+    ) {
+        let interaction_profile_id = match self.device_states.get(device_idx) {
+            Some((_, interaction_profile_id)) => *interaction_profile_id,
+            None => {
+                warn!("Disconnected device {:?} was never registered", device_idx);
+                return;
+            }
+        };
+
+        //The device has to stay registered until its held components have been released
         self.default_interaction_profiles
             .get_mut(&interaction_profile_id)
             .unwrap()
-            .device_added(device_idx, ty);
+            .device_removed(
+                device_idx,
+                runtime.get_time(Instant::now()),
+                &self.device_states,
+                |profile_state, user_path, event, devices| {
+                    self.user.on_interaction_profile_event(
+                        interaction_profile_id,
+                        profile_state,
+                        &self.active_action_sets,
+                        user_path,
+                        event,
+                        actions,
+                        callbacks,
+                        devices,
+                    );
+                },
+            );
+
+        self.device_states.remove(device_idx);
+        self.device_drivers.remove(&device_idx);
+    }
+
+    /// Returns the most recently updated state of the component across every interaction profile the user path is in,
+    /// asking the drivers of the user's devices if the session has not received an event for it yet
+    pub fn get_input_component_state(
+        &self,
+        runtime: &Runtime,
+        user_path: UserPath,
+        input_path: InputPath,
+    ) -> Option<InputComponentState> {
+        let profiles = self
+            .default_interaction_profiles
+            .values()
+            .filter(|profile| profile.ty.user2device.contains_key(&user_path));
+
+        if let Some(data) = profiles
+            .clone()
+            .filter_map(|profile| {
+                profile.get_input_component_state(
+                    user_path,
+                    profile.ty.resolve_alias(user_path, input_path),
+                )
+            })
+            .max_by_key(|data| data.last_update_time)
+        {
+            return Some(data.state);
+        }
+
+        profiles
+            .flat_map(|profile| {
+                let input_path = profile.ty.resolve_alias(user_path, input_path);
+                profile
+                    .get_devices(user_path)
+                    .map(move |device_idx| (device_idx, input_path))
+            })
+            .find_map(|(device_idx, input_path)| {
+                runtime.get_component_state(
+                    *self.device_drivers.get(&device_idx)?,
+                    device_idx.to_bits(),
+                    input_path,
+                )
+            })
+            .map(|event| match event {
+                InputComponentEvent::Button(state) => InputComponentState::Button(state),
+                InputComponentEvent::Trigger(state) => InputComponentState::Trigger(state),
+                InputComponentEvent::Joystick(state) => InputComponentState::Joystick(state.into()),
+                InputComponentEvent::Touchpad(state) => InputComponentState::Touchpad(state),
+                _ => InputComponentState::NonApplicable,
+            })
+    }
+
+    fn batch_input_event(
+        &mut self,
+        runtime: &Arc<Runtime>,
+        actions: &HashMap<u64, Arc<Action>>,
+        batch: BatchInputUpdate,
+        callbacks: &mut [Box<dyn ActionListener>],
+    ) {
+        let device_idx = Index::from_bits(batch.device).unwrap();
+
+        let device = match self.device_states.get_mut(device_idx) {
+            Some(device) => device,
+            None => return,
+        };
+
+        //Motion has to be updated before any gyro bindings are processed
+        if device.0.handle_batch(batch.time, &batch.inner).is_err() {
+            warn!("Failed to process batch update for device {:?}", device_idx);
+            return;
+        }
+
+        //Every component in the batch shares the same timestamp
+        let time = runtime.get_time(batch.time);
+
+        for (path, data) in batch.inner {
+            self.input_event(
+                actions,
+                InputEvent {
+                    device: batch.device,
+                    path,
+                    time,
+                    data,
+                },
+                callbacks,
+            );
+        }
     }
 
     fn input_event(
@@ -250,7 +456,11 @@ impl InnerSession {
     ) {
         let device_idx = Index::from_bits(event.device).unwrap();
 
-        let device = self.device_states.get_mut(device_idx).unwrap();
+        //Devices without an interaction profile are never registered
+        let device = match self.device_states.get_mut(device_idx) {
+            Some(device) => device,
+            None => return,
+        };
 
         if let Some(event) = device.0.process_input_event(event) {
             let (_, interaction_profile_id) = self.device_states.get(device_idx).unwrap();
@@ -1,6 +1,7 @@
 use std::time::Instant;
 
 use nalgebra::Vector2;
+use suinput_types::event::{Cursor, TouchpadState};
 
 #[derive(Debug, Clone, Copy)]
 pub struct InputComponentData {
@@ -13,6 +14,8 @@ pub enum InputComponentState {
     Button(bool),
     Trigger(f32),
     Joystick(Vector2<f32>),
+    Touchpad(TouchpadState),
+    Cursor(Cursor),
     NonApplicable,
 }
 
@@ -22,6 +25,8 @@ pub enum InputComponentType {
     Trigger,
     Move2D,
     Joystick,
+    Touchpad { max_points: u32, pressure: bool },
+    Cursor,
     Gyro(bool),
     Accel,
 }
@@ -34,3 +39,15 @@ pub enum InternalActionState {
     Axis2d(Vector2<f32>),
     NonApplicable,
 }
+
+impl InternalActionState {
+    /// Returns true if the binding isn't holding its action
+    pub fn is_idle(&self) -> bool {
+        match self {
+            InternalActionState::Boolean(state) => !state,
+            InternalActionState::Value(state) | InternalActionState::Axis1d(state) => *state == 0.,
+            InternalActionState::Axis2d(state) => state.x == 0. && state.y == 0.,
+            InternalActionState::NonApplicable => true,
+        }
+    }
+}
@@ -1,10 +1,10 @@
 use std::{collections::HashSet, time::Instant, vec::IntoIter};
 
 use suinput_types::{
-    event::{InputComponentEvent, InputEvent},
-    SuPath,
+    event::{InputComponentEvent, InputEvent, TouchpadState},
+    SuPath, Time,
 };
-use thunderdome::{Arena, Index};
+use thunderdome::Index;
 
 use super::{
     device::DeviceState,
@@ -85,6 +85,13 @@ impl InteractionProfileState {
                             event_device_id,
                         )
                         .map(InputComponentState::Joystick),
+                    //Touch points from different devices can't be meaningfully merged so the latest touchpad wins
+                    InputComponentEvent::Touchpad(state) => {
+                        Some(InputComponentState::Touchpad(state))
+                    }
+                    InputComponentEvent::Cursor(cursor) => {
+                        Some(InputComponentState::Cursor(cursor))
+                    }
                     InputComponentEvent::Gyro(_) =>
                     //TODO only have one active gyro for component per interaction profile
                     {
@@ -108,6 +115,11 @@ impl InteractionProfileState {
         }
     }
 
+    /// The devices currently attached to the user path
+    pub fn get_devices(&self, user_path: UserPath) -> impl Iterator<Item = Index> + '_ {
+        self.devices.get(&user_path).into_iter().flatten().copied()
+    }
+
     pub fn get_input_component_state(
         &self,
         user_path: UserPath,
@@ -132,8 +144,120 @@ impl InteractionProfileState {
         })
     }
 
-    pub fn device_removed(&mut self, _id: Index, _devices: &Arena<(DeviceState, Index)>) {
-        todo!()
+    /// Removes the device from every user path it was attached to and re-aggregates each component it was holding
+    /// against the remaining devices, so anything held only by the removed device is released
+    pub fn device_removed<
+        F: FnMut(
+            &InteractionProfileState,
+            UserPath,
+            &InputEvent,
+            &ParallelArena<(DeviceState, InteractionProfilePath)>,
+        ),
+    >(
+        &mut self,
+        device_id: Index,
+        time: Time,
+        devices: &ParallelArena<(DeviceState, InteractionProfilePath)>,
+        mut process_bindings: F,
+    ) {
+        let (device_state, _) = devices.get(device_id).unwrap();
+
+        let mut events = Vec::new();
+
+        for (user_path, device_ids) in self.devices.iter_mut() {
+            if !device_ids.remove(&device_id) {
+                continue;
+            }
+
+            for (input_path, data) in &device_state.input_component_states {
+                let mut remaining = device_ids
+                    .iter()
+                    .filter_map(|idx| devices.get(*idx))
+                    .filter_map(|(device_state, _)| {
+                        device_state.input_component_states.get(input_path)
+                    })
+                    .map(|data| data.state);
+
+                let new_state = match data.state {
+                    InputComponentState::Button(_) => {
+                        InputComponentEvent::Button(remaining.any(|state| bool::from_ics(&state)))
+                    }
+                    InputComponentState::Trigger(_) => InputComponentEvent::Trigger(
+                        remaining
+                            .map(|state| Value::from_ics(&state))
+                            .fold(0., f32::max),
+                    ),
+                    InputComponentState::Joystick(_) => InputComponentEvent::Joystick(
+                        remaining
+                            .map(|state| Axis2d::from_ics(&state))
+                            .max_by(|left, right| {
+                                left.magnitude_squared()
+                                    .total_cmp(&right.magnitude_squared())
+                            })
+                            .unwrap_or_default()
+                            .into(),
+                    ),
+                    InputComponentState::Touchpad(_) => {
+                        InputComponentEvent::Touchpad(TouchpadState::default())
+                    }
+                    //The cursor stays where it was last seen
+                    InputComponentState::Cursor(_) | InputComponentState::NonApplicable => continue,
+                };
+
+                events.push((
+                    *user_path,
+                    InputEvent {
+                        device: device_id.to_bits(),
+                        path: *input_path,
+                        time,
+                        data: new_state,
+                    },
+                ));
+            }
+        }
+
+        for (user_path, event) in events {
+            let new_state = match event.data {
+                InputComponentEvent::Button(state) => InputComponentState::Button(state),
+                InputComponentEvent::Trigger(state) => InputComponentState::Trigger(state),
+                InputComponentEvent::Joystick(state) => InputComponentState::Joystick(state.into()),
+                InputComponentEvent::Touchpad(state) => InputComponentState::Touchpad(state),
+                _ => unreachable!(),
+            };
+
+            let changed = match (
+                self.input_components
+                    .get(&(user_path, event.path))
+                    .map(|data| data.state),
+                new_state,
+            ) {
+                (Some(InputComponentState::Button(old)), InputComponentState::Button(new)) => {
+                    old != new
+                }
+                (Some(InputComponentState::Trigger(old)), InputComponentState::Trigger(new)) => {
+                    old != new
+                }
+                (Some(InputComponentState::Joystick(old)), InputComponentState::Joystick(new)) => {
+                    old != new
+                }
+                (Some(InputComponentState::Touchpad(old)), InputComponentState::Touchpad(new)) => {
+                    old != new
+                }
+                _ => false,
+            };
+
+            if changed {
+                process_bindings(self, user_path, &event, devices);
+
+                self.input_components.insert(
+                    (user_path, event.path),
+                    InputComponentData {
+                        last_update_time: Instant::now(),
+                        state: new_state,
+                    },
+                );
+            }
+        }
     }
 }
 
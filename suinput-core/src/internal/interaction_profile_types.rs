@@ -1,27 +1,48 @@
 use dashmap::{mapref::one::Ref, DashMap};
-use suinput_types::SuPath;
 
 use super::{
-    device_types::DeviceTypes, interaction_profile_type::InteractionProfileType,
-    paths::InteractionProfilePath,
+    config::serial_interaction_profile_type,
+    device_types::DeviceTypes,
+    interaction_profile_type::InteractionProfileType,
+    paths::{DevicePath, InteractionProfilePath, PathManager},
+    types::HashMap,
 };
+use crate::driver_interface::{InteractionProfileInfo, RuntimeInterfaceError};
+use crate::runtime::RuntimeConfigError;
 
-//TODO load interaction profiles from Drivers and KDL files
 pub struct InteractionProfileTypes {
     cache: DashMap<InteractionProfilePath, InteractionProfileType>,
+    //The first interaction profile to declare a device type is the one its devices are attached to
+    default_profiles: DashMap<DevicePath, InteractionProfilePath>,
 }
 
 impl InteractionProfileTypes {
-    pub fn new<F: Fn(&str) -> SuPath>(device_types: &DeviceTypes, get_path: F) -> Self {
-        let cache = DashMap::new();
+    pub fn new(
+        device_types: &DeviceTypes,
+        paths: &PathManager,
+    ) -> Result<Self, RuntimeConfigError> {
+        let interaction_profile_types = Self {
+            cache: DashMap::new(),
+            default_profiles: DashMap::new(),
+        };
 
-        let desktop = InteractionProfileType::new_desktop_profile(device_types, &get_path);
-        cache.insert(desktop.id, desktop);
+        for interaction_profile in
+            serial_interaction_profile_type::deserialize(paths, device_types)?
+        {
+            interaction_profile_types.insert(interaction_profile);
+        }
 
-        let dualsense = InteractionProfileType::new_dualsense_profile(device_types, &get_path);
-        cache.insert(dualsense.id, dualsense);
+        Ok(interaction_profile_types)
+    }
 
-        Self { cache }
+    fn insert(&self, interaction_profile: InteractionProfileType) {
+        for device_type in interaction_profile.device2user.keys() {
+            self.default_profiles
+                .entry(*device_type)
+                .or_insert(interaction_profile.id);
+        }
+        self.cache
+            .insert(interaction_profile.id, interaction_profile);
     }
 
     pub fn get(
@@ -30,4 +51,86 @@ impl InteractionProfileTypes {
     ) -> Option<Ref<'_, InteractionProfilePath, InteractionProfileType>> {
         self.cache.get(&path)
     }
+
+    pub fn get_default_profile(&self, device_type: DevicePath) -> Option<InteractionProfilePath> {
+        self.default_profiles.get(&device_type).map(|entry| *entry)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = InteractionProfileType> + '_ {
+        self.cache.iter().map(|entry| entry.value().clone())
+    }
+
+    pub fn register(
+        &self,
+        interaction_profile: InteractionProfileInfo,
+        device_types: &DeviceTypes,
+        paths: &PathManager,
+    ) -> Result<(), RuntimeInterfaceError> {
+        let get_path_string = |path| {
+            paths
+                .get_path_string(path)
+                .ok_or(RuntimeInterfaceError::InvalidPathHandle(path))
+        };
+
+        let id_string = get_path_string(interaction_profile.id)?;
+
+        if !id_string.starts_with("/interaction_profiles/") {
+            return Err(RuntimeInterfaceError::BadInteractionProfilePath(id_string));
+        }
+
+        let mut user2device = HashMap::new();
+
+        for (user_path, device_path) in interaction_profile.user2device {
+            let user_path_string = get_path_string(user_path)?;
+
+            if !user_path_string.starts_with("/user/") {
+                return Err(RuntimeInterfaceError::BadUserPath(user_path_string));
+            }
+
+            let device_type = match device_types.get(device_path) {
+                Some(device_type) => device_type.clone(),
+                None => {
+                    return Err(RuntimeInterfaceError::UnknownDeviceType(get_path_string(
+                        device_path,
+                    )?))
+                }
+            };
+
+            user2device.insert(user_path, device_type);
+        }
+
+        let mut aliases = HashMap::new();
+
+        for ((user_path, from), to) in interaction_profile.aliases {
+            if !user2device.contains_key(&user_path) {
+                return Err(RuntimeInterfaceError::BadUserPath(get_path_string(
+                    user_path,
+                )?));
+            }
+
+            for input_path in [from, to] {
+                let input_path_string = get_path_string(input_path)?;
+
+                if !input_path_string.starts_with("/input/") {
+                    return Err(RuntimeInterfaceError::BadComponentPath(input_path_string));
+                }
+            }
+
+            aliases.insert((user_path, from), to);
+        }
+
+        if self.cache.contains_key(&interaction_profile.id) {
+            return Err(RuntimeInterfaceError::InteractionProfileAlreadyRegistered(
+                id_string,
+            ));
+        }
+
+        self.insert(InteractionProfileType::new(
+            interaction_profile.id,
+            user2device,
+            aliases,
+        ));
+
+        Ok(())
+    }
 }
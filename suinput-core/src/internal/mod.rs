@@ -12,6 +12,7 @@ pub mod interaction_profile_type;
 pub mod interaction_profile_types;
 #[allow(dead_code)]
 pub mod motion;
+pub mod output_component;
 pub mod parallel_arena;
 pub mod paths;
 pub mod serial;
@@ -0,0 +1,31 @@
+use suinput_types::event::OutputComponentEvent;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputComponentType {
+    Rumble,
+    HdRumble,
+    Led,
+    PlayerNumber { min: u32, max: u32 },
+    AdaptiveTrigger,
+}
+
+impl OutputComponentType {
+    /// Returns true if the event can be sent to a component of this type
+    pub fn accepts(&self, event: &OutputComponentEvent) -> bool {
+        match (self, event) {
+            (
+                OutputComponentType::Rumble | OutputComponentType::HdRumble,
+                OutputComponentEvent::Vibration { .. },
+            ) => true,
+            (OutputComponentType::Led, OutputComponentEvent::Led(_)) => true,
+            (
+                OutputComponentType::PlayerNumber { min, max },
+                OutputComponentEvent::PlayerNumber(number),
+            ) => (*min..=*max).contains(number),
+            (OutputComponentType::AdaptiveTrigger, OutputComponentEvent::AdaptiveTrigger(_)) => {
+                true
+            }
+            _ => false,
+        }
+    }
+}
@@ -26,6 +26,13 @@ impl<T> ParallelArena<T> {
         }
     }
 
+    pub fn remove(&mut self, index: Index) -> Option<T> {
+        match self.storage.get_mut(index.slot() as usize) {
+            Some(entry) => entry.take(),
+            None => None,
+        }
+    }
+
     pub fn get(&self, index: Index) -> Option<&T> {
         match self.storage.get(index.slot() as usize) {
             Some(entry) => entry.as_ref(),
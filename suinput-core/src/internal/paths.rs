@@ -78,5 +78,6 @@ impl CommonPaths {
 //TODO start using these everywhere and then migrate them to structs
 pub type UserPath = SuPath;
 pub type InputPath = SuPath;
+pub type OutputPath = SuPath;
 pub type InteractionProfilePath = SuPath;
 pub type DevicePath = SuPath;
@@ -0,0 +1,1132 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use suinput_types::{
+    binding::{
+        activator, gyro, joystick, touchpad::TouchpadRegion, Binding as SuBinding, BooleanBinding,
+        FlickStickBinding, GyroBinding, JoystickBinding, SimpleBinding, StickAxisBinding,
+        StickCameraBinding, StickRegionBinding, TouchpadBinding, TriggerBinding,
+    },
+    SuPath,
+};
+
+/// A binding with its handles replaced by names
+///
+/// `kind` is the kebab-case name of the binding's variant and only the settings that kind uses are present.
+/// Settings that pick between variants carry the kebab-case variant name in their own `kind`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Binding {
+    pub kind: String,
+    pub action_set: String,
+    pub parent_action: Option<String>,
+    pub action: String,
+    pub input_component: String,
+    /// Modifiers the binding is gated on, outermost first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chords: Vec<Chord>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub activator: Option<Activator>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gyro_space: Option<GyroSpace>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gyro_filtering: Option<GyroFiltering>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invert: Option<Invert>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub processing: Option<Processing>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sensitivity: Option<Sensitivity>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub edge_acceleration: Option<EdgeAcceleration>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flick_stick: Option<FlickStick>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stick_region: Option<StickRegion>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stick_axis: Option<StickAxis>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger: Option<Trigger>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub touchpad: Option<Touchpad>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, knuffel::Decode)]
+pub struct Chord {
+    #[knuffel(argument)]
+    pub modifier: String,
+    #[knuffel(property(name = "type"))]
+    pub ty: String,
+    #[knuffel(property)]
+    pub threshold: f32,
+}
+
+/// `overridden`, `blocking`, `impulse` and the durations are only present for the activators that use them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, knuffel::Decode)]
+pub struct Activator {
+    #[knuffel(argument)]
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[knuffel(property)]
+    pub overridden: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[knuffel(property)]
+    pub blocking: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[knuffel(property)]
+    pub impulse: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[knuffel(property)]
+    pub duration: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[knuffel(property)]
+    pub taps: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, knuffel::Decode)]
+pub struct GyroSpace {
+    #[knuffel(argument)]
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[knuffel(property(name = "x-axis"))]
+    pub x_axis: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[knuffel(property(name = "yaw-factor"))]
+    pub yaw_factor: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[knuffel(property(name = "roll-factor"))]
+    pub roll_factor: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[knuffel(property(name = "relax-angle"))]
+    pub relax_angle: Option<f32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, knuffel::Decode)]
+pub struct GyroFiltering {
+    #[knuffel(property(name = "cut-off-speed"))]
+    pub cut_off_speed: f32,
+    #[knuffel(property(name = "cut-off-recovery"))]
+    pub cut_off_recovery: f32,
+    #[knuffel(property(name = "smooth-threshold"))]
+    pub smooth_threshold: f32,
+    #[knuffel(property(name = "smooth-time"))]
+    pub smooth_time: f32,
+}
+
+/// Which gyro axes are inverted
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, knuffel::Decode)]
+pub struct Invert {
+    #[knuffel(property)]
+    pub x: bool,
+    #[knuffel(property)]
+    pub y: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, knuffel::Decode)]
+pub struct Processing {
+    #[knuffel(property)]
+    pub shape: String,
+    #[knuffel(property(name = "inner-deadzone"))]
+    pub inner_deadzone: f32,
+    #[knuffel(property(name = "outer-deadzone"))]
+    pub outer_deadzone: f32,
+    #[knuffel(property(name = "anti-deadzone"))]
+    pub anti_deadzone: f32,
+    #[knuffel(property)]
+    pub curve: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[knuffel(property)]
+    pub exponent: Option<f32>,
+    /// Only used by piecewise curves
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[knuffel(children(name = "point"))]
+    pub points: Vec<CurvePoint>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, knuffel::Decode)]
+pub struct CurvePoint {
+    #[knuffel(argument)]
+    pub input: f32,
+    #[knuffel(argument)]
+    pub output: f32,
+}
+
+/// Linear sensitivities only use `scale`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, knuffel::Decode)]
+pub struct Sensitivity {
+    #[knuffel(argument)]
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[knuffel(property)]
+    pub scale: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[knuffel(property(name = "slow-threshold"))]
+    pub slow_threshold: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[knuffel(property(name = "fast-threshold"))]
+    pub fast_threshold: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[knuffel(property(name = "slow-scale"))]
+    pub slow_scale: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[knuffel(property(name = "fast-scale"))]
+    pub fast_scale: Option<f32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, knuffel::Decode)]
+pub struct EdgeAcceleration {
+    #[knuffel(property)]
+    pub threshold: f32,
+    #[knuffel(property(name = "ramp-time"))]
+    pub ramp_time: f32,
+    #[knuffel(property)]
+    pub multiplier: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, knuffel::Decode)]
+pub struct FlickStick {
+    #[knuffel(property(name = "flick-time"))]
+    pub flick_time: f32,
+    #[knuffel(property)]
+    pub threshold: f32,
+    #[knuffel(property)]
+    pub sensitivity: f64,
+}
+
+/// `direction` and `sectors` are only present for directions and `deadzone` for inner rings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, knuffel::Decode)]
+pub struct StickRegion {
+    #[knuffel(argument)]
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[knuffel(property)]
+    pub direction: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[knuffel(property)]
+    pub sectors: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[knuffel(property)]
+    pub deadzone: Option<f32>,
+    #[knuffel(property)]
+    pub threshold: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, knuffel::Decode)]
+pub struct StickAxis {
+    #[knuffel(argument)]
+    pub axis: String,
+    #[knuffel(property)]
+    pub invert: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, knuffel::Decode)]
+pub struct Trigger {
+    #[knuffel(property)]
+    pub press: f32,
+    #[knuffel(property)]
+    pub release: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, knuffel::Decode)]
+pub struct Touchpad {
+    #[knuffel(property)]
+    pub left: f32,
+    #[knuffel(property)]
+    pub top: f32,
+    #[knuffel(property)]
+    pub right: f32,
+    #[knuffel(property)]
+    pub bottom: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[knuffel(property)]
+    pub finger: Option<usize>,
+    #[knuffel(property(name = "sensitivity-x"))]
+    pub sensitivity_x: f64,
+    #[knuffel(property(name = "sensitivity-y"))]
+    pub sensitivity_y: f64,
+}
+
+impl Binding {
+    /// Sets `kind` and the settings it uses, chords have to be unwrapped by the caller
+    pub(crate) fn set_settings(&mut self, binding: &SuBinding) {
+        self.kind = match binding {
+            SuBinding::Simple(_) => "simple",
+            SuBinding::Boolean(boolean) => {
+                self.activator = Some(Activator::from(boolean.activator));
+                "boolean"
+            }
+            SuBinding::Gyro(gyro) => {
+                self.gyro_space = Some(GyroSpace::from(gyro.space));
+                self.gyro_filtering = Some(GyroFiltering::from(gyro.filtering));
+                self.sensitivity = Some(Sensitivity::from(gyro.sensitivity));
+                self.invert = Some(Invert {
+                    x: gyro.invert_x,
+                    y: gyro.invert_y,
+                });
+                "gyro"
+            }
+            SuBinding::Joystick(joystick) => {
+                self.processing = Some(Processing::from(&joystick.processing));
+                "joystick"
+            }
+            SuBinding::FlickStick(flick_stick) => {
+                self.flick_stick = Some(FlickStick {
+                    flick_time: flick_stick.flick_time,
+                    threshold: flick_stick.threshold,
+                    sensitivity: flick_stick.sensitivity,
+                });
+                "flick-stick"
+            }
+            SuBinding::StickCamera(stick_camera) => {
+                self.processing = Some(Processing::from(&stick_camera.processing));
+                self.sensitivity = Some(Sensitivity::from(stick_camera.sensitivity));
+                self.edge_acceleration =
+                    stick_camera
+                        .edge_acceleration
+                        .map(|edge_acceleration| EdgeAcceleration {
+                            threshold: edge_acceleration.threshold,
+                            ramp_time: edge_acceleration.ramp_time,
+                            multiplier: edge_acceleration.multiplier,
+                        });
+                "stick-camera"
+            }
+            SuBinding::StickRegion(stick_region) => {
+                self.stick_region = Some(StickRegion::from(stick_region.region));
+                "stick-region"
+            }
+            SuBinding::StickAxis(stick_axis) => {
+                self.processing = Some(Processing::from(&stick_axis.processing));
+                self.stick_axis = Some(StickAxis {
+                    axis: name(stick_axis.axis),
+                    invert: stick_axis.invert,
+                });
+                "stick-axis"
+            }
+            SuBinding::Trigger(trigger) => {
+                self.trigger = Some(Trigger {
+                    press: trigger.press_threshold,
+                    release: trigger.release_threshold,
+                });
+                "trigger"
+            }
+            SuBinding::Touchpad(touchpad) => {
+                self.touchpad = Some(Touchpad {
+                    left: touchpad.region.left,
+                    top: touchpad.region.top,
+                    right: touchpad.region.right,
+                    bottom: touchpad.region.bottom,
+                    finger: touchpad.finger,
+                    sensitivity_x: touchpad.sensitivity.0,
+                    sensitivity_y: touchpad.sensitivity.1,
+                });
+                "touchpad"
+            }
+            SuBinding::Chord(_) => unreachable!("chords are unwrapped by the caller"),
+        }
+        .into();
+    }
+
+    /// False if a setting is NaN or infinite, neither format can hold those
+    pub(crate) fn is_finite(&self) -> bool {
+        let mut floats = self
+            .chords
+            .iter()
+            .map(|chord| chord.threshold)
+            .collect::<Vec<_>>();
+        let mut doubles = Vec::new();
+
+        if let Some(space) = &self.gyro_space {
+            floats.extend(
+                [space.yaw_factor, space.roll_factor, space.relax_angle]
+                    .into_iter()
+                    .flatten(),
+            );
+        }
+        if let Some(filtering) = &self.gyro_filtering {
+            floats.extend([
+                filtering.cut_off_speed,
+                filtering.cut_off_recovery,
+                filtering.smooth_threshold,
+                filtering.smooth_time,
+            ]);
+        }
+        if let Some(processing) = &self.processing {
+            floats.extend([
+                processing.inner_deadzone,
+                processing.outer_deadzone,
+                processing.anti_deadzone,
+            ]);
+            floats.extend(processing.exponent);
+            floats.extend(
+                processing
+                    .points
+                    .iter()
+                    .flat_map(|point| [point.input, point.output]),
+            );
+        }
+        if let Some(sensitivity) = &self.sensitivity {
+            floats.extend(
+                [
+                    sensitivity.scale,
+                    sensitivity.slow_threshold,
+                    sensitivity.fast_threshold,
+                    sensitivity.slow_scale,
+                    sensitivity.fast_scale,
+                ]
+                .into_iter()
+                .flatten(),
+            );
+        }
+        if let Some(edge_acceleration) = &self.edge_acceleration {
+            floats.extend([
+                edge_acceleration.threshold,
+                edge_acceleration.ramp_time,
+                edge_acceleration.multiplier,
+            ]);
+        }
+        if let Some(flick_stick) = &self.flick_stick {
+            floats.extend([flick_stick.flick_time, flick_stick.threshold]);
+            doubles.push(flick_stick.sensitivity);
+        }
+        if let Some(region) = &self.stick_region {
+            floats.extend(region.deadzone);
+            floats.push(region.threshold);
+        }
+        if let Some(trigger) = &self.trigger {
+            floats.extend([trigger.press, trigger.release]);
+        }
+        if let Some(touchpad) = &self.touchpad {
+            floats.extend([touchpad.left, touchpad.top, touchpad.right, touchpad.bottom]);
+            doubles.extend([touchpad.sensitivity_x, touchpad.sensitivity_y]);
+        }
+
+        floats.iter().all(|float| float.is_finite())
+            && doubles.iter().all(|double| double.is_finite())
+    }
+
+    /// Builds the binding described by `kind` and its settings, chords are applied by the caller
+    pub(crate) fn to_binding(&self, output: u64, input: SuPath) -> Result<SuBinding, String> {
+        Ok(match self.kind.as_str() {
+            "simple" => SimpleBinding {
+                action: output,
+                path: input,
+            }
+            .into(),
+            "boolean" => BooleanBinding {
+                input,
+                activator: required(&self.activator, "activator")?.to_activator()?,
+                output,
+            }
+            .into(),
+            "gyro" => {
+                let invert = required(&self.invert, "invert")?;
+                GyroBinding {
+                    input,
+                    space: required(&self.gyro_space, "gyro_space")?.to_space()?,
+                    filtering: required(&self.gyro_filtering, "gyro_filtering")?.into(),
+                    sensitivity: required(&self.sensitivity, "sensitivity")?.to_sensitivity()?,
+                    invert_x: invert.x,
+                    invert_y: invert.y,
+                    output,
+                }
+                .into()
+            }
+            "joystick" => JoystickBinding {
+                input,
+                processing: required(&self.processing, "processing")?.to_processing()?,
+                output,
+            }
+            .into(),
+            "flick-stick" => {
+                let flick_stick = required(&self.flick_stick, "flick_stick")?;
+                FlickStickBinding {
+                    input,
+                    flick_time: flick_stick.flick_time,
+                    threshold: flick_stick.threshold,
+                    sensitivity: flick_stick.sensitivity,
+                    output,
+                }
+                .into()
+            }
+            "stick-camera" => StickCameraBinding {
+                input,
+                processing: required(&self.processing, "processing")?.to_processing()?,
+                sensitivity: required(&self.sensitivity, "sensitivity")?.to_sensitivity()?,
+                edge_acceleration: self.edge_acceleration.map(|edge_acceleration| {
+                    joystick::EdgeAcceleration {
+                        threshold: edge_acceleration.threshold,
+                        ramp_time: edge_acceleration.ramp_time,
+                        multiplier: edge_acceleration.multiplier,
+                    }
+                }),
+                output,
+            }
+            .into(),
+            "stick-region" => StickRegionBinding {
+                input,
+                region: required(&self.stick_region, "stick_region")?.to_region()?,
+                output,
+            }
+            .into(),
+            "stick-axis" => {
+                let stick_axis = required(&self.stick_axis, "stick_axis")?;
+                StickAxisBinding {
+                    input,
+                    axis: parse(&stick_axis.axis, "stick axis")?,
+                    processing: required(&self.processing, "processing")?.to_processing()?,
+                    invert: stick_axis.invert,
+                    output,
+                }
+                .into()
+            }
+            "trigger" => {
+                let trigger = required(&self.trigger, "trigger")?;
+                TriggerBinding {
+                    input,
+                    press_threshold: trigger.press,
+                    release_threshold: trigger.release,
+                    output,
+                }
+                .into()
+            }
+            "touchpad" => {
+                let touchpad = required(&self.touchpad, "touchpad")?;
+                TouchpadBinding {
+                    input,
+                    region: TouchpadRegion {
+                        left: touchpad.left,
+                        top: touchpad.top,
+                        right: touchpad.right,
+                        bottom: touchpad.bottom,
+                    },
+                    finger: touchpad.finger,
+                    sensitivity: (touchpad.sensitivity_x, touchpad.sensitivity_y),
+                    output,
+                }
+                .into()
+            }
+            kind => return Err(format!("Unknown binding kind `{kind}`")),
+        })
+    }
+}
+
+impl From<activator::Activator> for Activator {
+    fn from(activator: activator::Activator) -> Self {
+        let empty = |kind: &str| Activator {
+            kind: kind.into(),
+            overridden: None,
+            blocking: None,
+            impulse: None,
+            duration: None,
+            taps: None,
+        };
+
+        match activator {
+            activator::Activator::Hold {
+                overridden_behavior,
+                impulse,
+            } => Activator {
+                overridden: Some(name(overridden_behavior)),
+                impulse: Some(name(impulse)),
+                ..empty("hold")
+            },
+            activator::Activator::QuickTap {
+                overridden_behavior,
+                blocking,
+                max_hold_duration,
+            } => Activator {
+                overridden: Some(name(overridden_behavior)),
+                blocking: Some(blocking),
+                duration: Some(max_hold_duration),
+                ..empty("quick-tap")
+            },
+            activator::Activator::MultiTap {
+                overridden_behavior,
+                blocking,
+                duration,
+                impulse,
+                taps,
+            } => Activator {
+                overridden: Some(name(overridden_behavior)),
+                blocking: Some(blocking),
+                impulse: Some(name(impulse)),
+                duration: Some(duration),
+                taps: Some(taps),
+                ..empty("multi-tap")
+            },
+            activator::Activator::LongHold {
+                min_hold_duration,
+                impulse,
+            } => Activator {
+                impulse: Some(name(impulse)),
+                duration: Some(min_hold_duration),
+                ..empty("long-hold")
+            },
+        }
+    }
+}
+
+impl Activator {
+    fn to_activator(&self) -> Result<activator::Activator, String> {
+        let overridden = || required(&self.overridden, "overridden");
+        let impulse = || parse(required(&self.impulse, "impulse")?, "impulse");
+        let blocking = || required(&self.blocking, "blocking").copied();
+        let duration = || required(&self.duration, "duration").copied();
+
+        Ok(match self.kind.as_str() {
+            "hold" => activator::Activator::Hold {
+                overridden_behavior: parse(overridden()?, "overridden behavior")?,
+                impulse: impulse()?,
+            },
+            "quick-tap" => activator::Activator::QuickTap {
+                overridden_behavior: parse(overridden()?, "overridden behavior")?,
+                blocking: blocking()?,
+                max_hold_duration: duration()?,
+            },
+            "multi-tap" => activator::Activator::MultiTap {
+                overridden_behavior: parse(overridden()?, "overridden behavior")?,
+                blocking: blocking()?,
+                duration: duration()?,
+                impulse: impulse()?,
+                taps: *required(&self.taps, "taps")?,
+            },
+            "long-hold" => activator::Activator::LongHold {
+                min_hold_duration: duration()?,
+                impulse: impulse()?,
+            },
+            kind => return Err(format!("Unknown activator `{kind}`")),
+        })
+    }
+}
+
+impl From<gyro::GyroSpace> for GyroSpace {
+    fn from(space: gyro::GyroSpace) -> Self {
+        let empty = |kind: &str| GyroSpace {
+            kind: kind.into(),
+            x_axis: None,
+            yaw_factor: None,
+            roll_factor: None,
+            relax_angle: None,
+        };
+
+        match space {
+            gyro::GyroSpace::LocalSpace { x_axis } => GyroSpace {
+                x_axis: Some(name(x_axis)),
+                ..empty("local-space")
+            },
+            gyro::GyroSpace::LocalCombinedYawRoll {
+                yaw_factor,
+                roll_factor,
+            } => GyroSpace {
+                yaw_factor: Some(yaw_factor),
+                roll_factor: Some(roll_factor),
+                ..empty("local-combined-yaw-roll")
+            },
+            gyro::GyroSpace::PlayerSpace {
+                relax_angle,
+                x_axis,
+            } => GyroSpace {
+                x_axis: Some(name(x_axis)),
+                relax_angle: Some(relax_angle),
+                ..empty("player-space")
+            },
+            gyro::GyroSpace::WorldSpace { x_axis } => GyroSpace {
+                x_axis: Some(name(x_axis)),
+                ..empty("world-space")
+            },
+        }
+    }
+}
+
+impl GyroSpace {
+    fn to_space(&self) -> Result<gyro::GyroSpace, String> {
+        let x_axis = || parse(required(&self.x_axis, "x_axis")?, "x axis");
+
+        Ok(match self.kind.as_str() {
+            "local-space" => gyro::GyroSpace::LocalSpace { x_axis: x_axis()? },
+            "local-combined-yaw-roll" => gyro::GyroSpace::LocalCombinedYawRoll {
+                yaw_factor: *required(&self.yaw_factor, "yaw_factor")?,
+                roll_factor: *required(&self.roll_factor, "roll_factor")?,
+            },
+            "player-space" => gyro::GyroSpace::PlayerSpace {
+                relax_angle: *required(&self.relax_angle, "relax_angle")?,
+                x_axis: x_axis()?,
+            },
+            "world-space" => gyro::GyroSpace::WorldSpace { x_axis: x_axis()? },
+            kind => return Err(format!("Unknown gyro space `{kind}`")),
+        })
+    }
+}
+
+impl From<gyro::GyroFiltering> for GyroFiltering {
+    fn from(filtering: gyro::GyroFiltering) -> Self {
+        Self {
+            cut_off_speed: filtering.cut_off_speed,
+            cut_off_recovery: filtering.cut_off_recovery,
+            smooth_threshold: filtering.smooth_threshold,
+            smooth_time: filtering.smooth_time,
+        }
+    }
+}
+
+impl From<&GyroFiltering> for gyro::GyroFiltering {
+    fn from(filtering: &GyroFiltering) -> Self {
+        Self {
+            cut_off_speed: filtering.cut_off_speed,
+            cut_off_recovery: filtering.cut_off_recovery,
+            smooth_threshold: filtering.smooth_threshold,
+            smooth_time: filtering.smooth_time,
+        }
+    }
+}
+
+impl From<&joystick::JoystickProcessing> for Processing {
+    fn from(processing: &joystick::JoystickProcessing) -> Self {
+        let (curve, exponent, points) = match &processing.curve {
+            joystick::ResponseCurve::Linear => ("linear", None, Vec::new()),
+            joystick::ResponseCurve::Power(exponent) => ("power", Some(*exponent), Vec::new()),
+            joystick::ResponseCurve::Piecewise(points) => (
+                "piecewise",
+                None,
+                points
+                    .iter()
+                    .map(|&(input, output)| CurvePoint { input, output })
+                    .collect(),
+            ),
+        };
+
+        Self {
+            shape: name(processing.deadzone_shape),
+            inner_deadzone: processing.inner_deadzone,
+            outer_deadzone: processing.outer_deadzone,
+            anti_deadzone: processing.anti_deadzone,
+            curve: curve.into(),
+            exponent,
+            points,
+        }
+    }
+}
+
+impl Processing {
+    fn to_processing(&self) -> Result<joystick::JoystickProcessing, String> {
+        Ok(joystick::JoystickProcessing {
+            deadzone_shape: parse(&self.shape, "deadzone shape")?,
+            inner_deadzone: self.inner_deadzone,
+            outer_deadzone: self.outer_deadzone,
+            anti_deadzone: self.anti_deadzone,
+            curve: match self.curve.as_str() {
+                "linear" => joystick::ResponseCurve::Linear,
+                "power" => joystick::ResponseCurve::Power(*required(&self.exponent, "exponent")?),
+                "piecewise" => joystick::ResponseCurve::Piecewise(
+                    self.points
+                        .iter()
+                        .map(|point| (point.input, point.output))
+                        .collect(),
+                ),
+                curve => return Err(format!("Unknown response curve `{curve}`")),
+            },
+        })
+    }
+}
+
+impl From<suinput_types::binding::Sensitivity<f32>> for Sensitivity {
+    fn from(sensitivity: suinput_types::binding::Sensitivity<f32>) -> Self {
+        match sensitivity {
+            suinput_types::binding::Sensitivity::Linear(scale) => Self {
+                kind: "linear".into(),
+                scale: Some(scale),
+                slow_threshold: None,
+                fast_threshold: None,
+                slow_scale: None,
+                fast_scale: None,
+            },
+            suinput_types::binding::Sensitivity::Acceleration {
+                slow_threshold,
+                fast_threshold,
+                slow_scale,
+                fast_scale,
+            } => Self {
+                kind: "acceleration".into(),
+                scale: None,
+                slow_threshold: Some(slow_threshold),
+                fast_threshold: Some(fast_threshold),
+                slow_scale: Some(slow_scale),
+                fast_scale: Some(fast_scale),
+            },
+        }
+    }
+}
+
+impl Sensitivity {
+    fn to_sensitivity(&self) -> Result<suinput_types::binding::Sensitivity<f32>, String> {
+        Ok(match self.kind.as_str() {
+            "linear" => {
+                suinput_types::binding::Sensitivity::Linear(*required(&self.scale, "scale")?)
+            }
+            "acceleration" => suinput_types::binding::Sensitivity::Acceleration {
+                slow_threshold: *required(&self.slow_threshold, "slow_threshold")?,
+                fast_threshold: *required(&self.fast_threshold, "fast_threshold")?,
+                slow_scale: *required(&self.slow_scale, "slow_scale")?,
+                fast_scale: *required(&self.fast_scale, "fast_scale")?,
+            },
+            kind => return Err(format!("Unknown sensitivity `{kind}`")),
+        })
+    }
+}
+
+impl From<joystick::StickRegion> for StickRegion {
+    fn from(region: joystick::StickRegion) -> Self {
+        match region {
+            joystick::StickRegion::Direction {
+                direction,
+                sectors,
+                threshold,
+            } => Self {
+                kind: "direction".into(),
+                direction: Some(name(direction)),
+                sectors: Some(name(sectors)),
+                deadzone: None,
+                threshold,
+            },
+            joystick::StickRegion::OuterRing { threshold } => Self {
+                kind: "outer-ring".into(),
+                direction: None,
+                sectors: None,
+                deadzone: None,
+                threshold,
+            },
+            joystick::StickRegion::InnerRing {
+                deadzone,
+                threshold,
+            } => Self {
+                kind: "inner-ring".into(),
+                direction: None,
+                sectors: None,
+                deadzone: Some(deadzone),
+                threshold,
+            },
+        }
+    }
+}
+
+impl StickRegion {
+    fn to_region(&self) -> Result<joystick::StickRegion, String> {
+        Ok(match self.kind.as_str() {
+            "direction" => joystick::StickRegion::Direction {
+                direction: parse(required(&self.direction, "direction")?, "direction")?,
+                sectors: parse(required(&self.sectors, "sectors")?, "sectors")?,
+                threshold: self.threshold,
+            },
+            "outer-ring" => joystick::StickRegion::OuterRing {
+                threshold: self.threshold,
+            },
+            "inner-ring" => joystick::StickRegion::InnerRing {
+                deadzone: *required(&self.deadzone, "deadzone")?,
+                threshold: self.threshold,
+            },
+            kind => return Err(format!("Unknown stick region `{kind}`")),
+        })
+    }
+}
+
+/// The kebab-case name of a unit variant
+pub(crate) fn name<T: Into<&'static str>>(value: T) -> String {
+    value.into().to_owned()
+}
+
+pub(crate) fn parse<T: FromStr>(value: &str, what: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Unknown {what} `{value}`"))
+}
+
+fn required<'a, T>(setting: &'a Option<T>, setting_name: &str) -> Result<&'a T, String> {
+    setting
+        .as_ref()
+        .ok_or_else(|| format!("Missing `{setting_name}`"))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use suinput_types::binding::{chord::ChordType, ChordBinding};
+
+    use super::*;
+    use crate::action::ActionTypeEnum;
+
+    /// A binding of every kind on a dualsense covering each variant of their settings
+    ///
+    /// `action` is asked for a new action of the type each binding outputs to
+    pub(crate) fn every_kind(
+        mut action: impl FnMut(ActionTypeEnum) -> u64,
+        path: impl Fn(&str) -> SuPath,
+    ) -> Vec<SuBinding> {
+        let processing = |curve| joystick::JoystickProcessing {
+            deadzone_shape: joystick::DeadzoneShape::Hybrid,
+            inner_deadzone: 0.1,
+            outer_deadzone: 0.05,
+            anti_deadzone: 0.2,
+            curve,
+        };
+        let acceleration = suinput_types::binding::Sensitivity::Acceleration {
+            slow_threshold: 5.,
+            fast_threshold: 75.,
+            slow_scale: 0.5,
+            fast_scale: 2.,
+        };
+
+        let mut bindings: Vec<SuBinding> = vec![
+            SimpleBinding {
+                action: action(ActionTypeEnum::Boolean),
+                path: path("/user/gamepad/input/diamond_down/click"),
+            }
+            .into(),
+            ChordBinding {
+                modifier: path("/user/gamepad/input/trigger_left/value"),
+                ty: ChordType::PressToEnable,
+                threshold: 0.5,
+                binding: Box::new(
+                    ChordBinding {
+                        modifier: path("/user/gamepad/input/shoulder_left/click"),
+                        ty: ChordType::Toggle,
+                        threshold: 0.,
+                        binding: Box::new(
+                            SimpleBinding {
+                                action: action(ActionTypeEnum::Boolean),
+                                path: path("/user/gamepad/input/options/click"),
+                            }
+                            .into(),
+                        ),
+                    }
+                    .into(),
+                ),
+            }
+            .into(),
+            GyroBinding {
+                input: path("/user/gamepad/input/motion/gyro"),
+                space: gyro::GyroSpace::PlayerSpace {
+                    relax_angle: 60.,
+                    x_axis: gyro::XAxis::Roll,
+                },
+                filtering: gyro::GyroFiltering {
+                    cut_off_speed: 0.5,
+                    cut_off_recovery: 1.,
+                    smooth_threshold: 2.,
+                    smooth_time: 0.25,
+                },
+                sensitivity: acceleration,
+                invert_x: true,
+                invert_y: false,
+                output: action(ActionTypeEnum::Delta2d),
+            }
+            .into(),
+            GyroBinding {
+                input: path("/user/gamepad/input/motion/gyro"),
+                space: gyro::GyroSpace::LocalCombinedYawRoll {
+                    yaw_factor: 1.,
+                    roll_factor: 0.5,
+                },
+                filtering: Default::default(),
+                sensitivity: suinput_types::binding::Sensitivity::Linear(3.),
+                invert_x: false,
+                invert_y: true,
+                output: action(ActionTypeEnum::Delta2d),
+            }
+            .into(),
+            JoystickBinding {
+                input: path("/user/gamepad/input/joystick_left/position"),
+                processing: processing(joystick::ResponseCurve::Piecewise(vec![
+                    (0.25, 0.1),
+                    (0.75, 0.5),
+                ])),
+                output: action(ActionTypeEnum::Axis2d),
+            }
+            .into(),
+            FlickStickBinding {
+                input: path("/user/gamepad/input/joystick_right/position"),
+                flick_time: 0.1,
+                threshold: 0.9,
+                sensitivity: 2.5,
+                output: action(ActionTypeEnum::Delta2d),
+            }
+            .into(),
+            StickCameraBinding {
+                input: path("/user/gamepad/input/joystick_right/position"),
+                processing: processing(joystick::ResponseCurve::Power(2.)),
+                sensitivity: acceleration,
+                edge_acceleration: Some(joystick::EdgeAcceleration {
+                    threshold: 0.95,
+                    ramp_time: 0.5,
+                    multiplier: 3.,
+                }),
+                output: action(ActionTypeEnum::Delta2d),
+            }
+            .into(),
+            StickRegionBinding {
+                input: path("/user/gamepad/input/joystick_left/position"),
+                region: joystick::StickRegion::Direction {
+                    direction: joystick::Direction::UpLeft,
+                    sectors: joystick::Sectors::Eight,
+                    threshold: 0.5,
+                },
+                output: action(ActionTypeEnum::Boolean),
+            }
+            .into(),
+            StickRegionBinding {
+                input: path("/user/gamepad/input/joystick_left/position"),
+                region: joystick::StickRegion::InnerRing {
+                    deadzone: 0.1,
+                    threshold: 0.6,
+                },
+                output: action(ActionTypeEnum::Boolean),
+            }
+            .into(),
+            StickRegionBinding {
+                input: path("/user/gamepad/input/joystick_left/position"),
+                region: joystick::StickRegion::OuterRing { threshold: 0.9 },
+                output: action(ActionTypeEnum::Boolean),
+            }
+            .into(),
+            StickAxisBinding {
+                input: path("/user/gamepad/input/joystick_left/position"),
+                axis: joystick::StickAxis::Y,
+                processing: processing(joystick::ResponseCurve::Linear),
+                invert: true,
+                output: action(ActionTypeEnum::Axis1d),
+            }
+            .into(),
+            TriggerBinding {
+                input: path("/user/gamepad/input/trigger_right/value"),
+                press_threshold: 0.6,
+                release_threshold: 0.4,
+                output: action(ActionTypeEnum::Boolean),
+            }
+            .into(),
+            TouchpadBinding {
+                input: path("/user/gamepad/input/touchpad/points"),
+                region: TouchpadRegion {
+                    left: 0.5,
+                    top: 0.,
+                    right: 1.,
+                    bottom: 0.5,
+                },
+                finger: Some(1),
+                sensitivity: (2., 3.),
+                output: action(ActionTypeEnum::Cursor),
+            }
+            .into(),
+        ];
+
+        let activators = [
+            activator::Activator::Hold {
+                overridden_behavior: activator::OverriddenBehavior::Interrupt,
+                impulse: activator::Impulse::OnRelease,
+            },
+            activator::Activator::QuickTap {
+                overridden_behavior: activator::ImpulseOverriddenBehavior::Block,
+                blocking: true,
+                max_hold_duration: 150,
+            },
+            activator::Activator::MultiTap {
+                overridden_behavior: activator::OverriddenBehavior::None,
+                blocking: false,
+                duration: 200,
+                impulse: activator::Impulse::OnPress,
+                taps: 2,
+            },
+            activator::Activator::LongHold {
+                min_hold_duration: 500,
+                impulse: activator::Impulse::False,
+            },
+        ];
+        bindings.extend(activators.into_iter().map(|activator| {
+            BooleanBinding {
+                input: path("/user/gamepad/input/diamond_right/click"),
+                activator,
+                output: action(ActionTypeEnum::Boolean),
+            }
+            .into()
+        }));
+
+        bindings
+    }
+
+    #[test]
+    fn settings_round_trip() {
+        let mut handle = 0;
+        let bindings = every_kind(
+            |_| {
+                handle += 1;
+                handle
+            },
+            |_| SuPath(1),
+        );
+
+        for binding in bindings {
+            //Chords are resolved along with the names
+            if let SuBinding::Chord(_) = binding {
+                continue;
+            }
+
+            let mut serial = Binding::default();
+            serial.set_settings(&binding);
+
+            let json = serde_json::to_string(&serial).unwrap();
+            let serial = serde_json::from_str::<Binding>(&json).unwrap();
+
+            let round_trip = serial.to_binding(binding.action(), binding.path()).unwrap();
+            assert_eq!(format!("{round_trip:?}"), format!("{binding:?}"));
+        }
+    }
+
+    #[test]
+    fn missing_settings() {
+        let serial = Binding {
+            kind: "trigger".into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            serial.to_binding(1, SuPath(1)).unwrap_err(),
+            "Missing `trigger`"
+        );
+
+        let serial = Binding {
+            kind: "stick-axis".into(),
+            stick_axis: Some(StickAxis {
+                axis: "z".into(),
+                invert: false,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            serial.to_binding(1, SuPath(1)).unwrap_err(),
+            "Unknown stick axis `z`"
+        );
+    }
+
+    #[test]
+    fn non_finite_settings() {
+        let mut serial = Binding {
+            kind: "trigger".into(),
+            trigger: Some(Trigger {
+                press: 0.6,
+                release: 0.4,
+            }),
+            ..Default::default()
+        };
+        assert!(serial.is_finite());
+
+        serial.trigger = Some(Trigger {
+            press: f32::NAN,
+            release: 0.4,
+        });
+        assert!(!serial.is_finite());
+
+        serial.trigger = None;
+        serial.chords.push(Chord {
+            modifier: "/user/gamepad/input/trigger_left/value".into(),
+            ty: "press-to-enable".into(),
+            threshold: f32::INFINITY,
+        });
+        assert!(!serial.is_finite());
+    }
+}
@@ -0,0 +1,805 @@
+use std::{ffi::OsStr, fmt::Write, path::Path, sync::Arc};
+
+use miette::Diagnostic;
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use suinput_types::{
+    binding::{Binding as SuBinding, ChordBinding},
+    CreateBindingLayoutError,
+};
+
+use super::{
+    binding::{
+        self, Activator, Chord, EdgeAcceleration, FlickStick, GyroFiltering, GyroSpace, Invert,
+        Processing, Sensitivity, StickAxis, StickRegion, Touchpad, Trigger,
+    },
+    Binding, BindingLayout,
+};
+use crate::{
+    action::{Action, ActionCompoundType},
+    instance::{self, Instance},
+    internal::binding::binding_engine::processed_binding_layout::ProcessedBindingLayout,
+    types::{Error, Result},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileFormat {
+    Json,
+    Kdl,
+}
+
+impl FileFormat {
+    fn from_path(file_path: &Path) -> Option<Self> {
+        match file_path.extension().and_then(OsStr::to_str) {
+            Some("json") => Some(Self::Json),
+            Some("kdl") => Some(Self::Kdl),
+            _ => None,
+        }
+    }
+}
+
+/// The interaction profile and bindings are borrowed so they can be traced back to their lines
+#[derive(Deserialize)]
+struct JsonBindingLayout<'a> {
+    name: String,
+    #[serde(borrow)]
+    interaction_profile: &'a RawValue,
+    #[serde(borrow)]
+    bindings: Vec<&'a RawValue>,
+}
+
+#[derive(knuffel::Decode)]
+#[knuffel(span_type = knuffel::span::Span)]
+struct KdlBindingLayout {
+    #[knuffel(span)]
+    span: knuffel::span::Span,
+    #[knuffel(property)]
+    name: String,
+    #[knuffel(property(name = "interaction-profile"))]
+    interaction_profile: String,
+    #[knuffel(children(name = "binding"))]
+    bindings: Vec<KdlBinding>,
+}
+
+/// A [`Binding`] with the kind as its argument and each setting as a child node
+#[derive(knuffel::Decode)]
+#[knuffel(span_type = knuffel::span::Span)]
+struct KdlBinding {
+    #[knuffel(span)]
+    span: knuffel::span::Span,
+    #[knuffel(argument)]
+    kind: String,
+    #[knuffel(property(name = "action-set"))]
+    action_set: String,
+    #[knuffel(property)]
+    parent: Option<String>,
+    #[knuffel(property)]
+    action: String,
+    #[knuffel(property)]
+    input: String,
+    #[knuffel(children(name = "chord"))]
+    chords: Vec<Chord>,
+    #[knuffel(child)]
+    activator: Option<Activator>,
+    #[knuffel(child)]
+    gyro_space: Option<GyroSpace>,
+    #[knuffel(child)]
+    gyro_filtering: Option<GyroFiltering>,
+    #[knuffel(child)]
+    invert: Option<Invert>,
+    #[knuffel(child)]
+    processing: Option<Processing>,
+    #[knuffel(child)]
+    sensitivity: Option<Sensitivity>,
+    #[knuffel(child)]
+    edge_acceleration: Option<EdgeAcceleration>,
+    #[knuffel(child)]
+    flick_stick: Option<FlickStick>,
+    #[knuffel(child)]
+    stick_region: Option<StickRegion>,
+    #[knuffel(child)]
+    stick_axis: Option<StickAxis>,
+    #[knuffel(child)]
+    trigger: Option<Trigger>,
+    #[knuffel(child)]
+    touchpad: Option<Touchpad>,
+}
+
+impl From<KdlBinding> for Binding {
+    fn from(binding: KdlBinding) -> Self {
+        Self {
+            kind: binding.kind,
+            action_set: binding.action_set,
+            parent_action: binding.parent,
+            action: binding.action,
+            input_component: binding.input,
+            chords: binding.chords,
+            activator: binding.activator,
+            gyro_space: binding.gyro_space,
+            gyro_filtering: binding.gyro_filtering,
+            invert: binding.invert,
+            processing: binding.processing,
+            sensitivity: binding.sensitivity,
+            edge_acceleration: binding.edge_acceleration,
+            flick_stick: binding.flick_stick,
+            stick_region: binding.stick_region,
+            stick_axis: binding.stick_axis,
+            trigger: binding.trigger,
+            touchpad: binding.touchpad,
+        }
+    }
+}
+
+/// Writes the binding layout as JSON or KDL depending on the file extension
+pub(crate) fn save(
+    instance: &Instance,
+    binding_layout: &instance::BindingLayout,
+    file_path: &Path,
+) -> Result<()> {
+    let format = FileFormat::from_path(file_path).ok_or(Error::ValidationFailure)?;
+    let interaction_profile = instance
+        .get_path_string(binding_layout.interaction_profile)
+        .ok_or(Error::ValidationFailure)?;
+
+    let serial = BindingLayout {
+        name: binding_layout.name.clone(),
+        interaction_profile,
+        bindings: binding_layout
+            .bindings
+            .iter()
+            .map(|binding| serialize_binding(instance, binding))
+            .collect::<Option<_>>()
+            .ok_or(Error::ValidationFailure)?,
+    };
+
+    //JSON would write them as null and KDL has no syntax for them, so they wouldn't load again
+    if !serial.bindings.iter().all(Binding::is_finite) {
+        return Err(Error::ValidationFailure);
+    }
+
+    let text = match format {
+        FileFormat::Json => {
+            serde_json::to_string_pretty(&serial).map_err(|_| Error::RuntimeFailure)?
+        }
+        FileFormat::Kdl => to_kdl(&serial),
+    };
+
+    if let Some(parent) = file_path.parent() {
+        std::fs::create_dir_all(parent).map_err(|_| Error::IoError)?;
+    }
+
+    std::fs::write(file_path, text).map_err(|_| Error::IoError)
+}
+
+/// Reads a binding layout saved by [`save`], errors in the file are reported with the line of the offending binding
+pub(crate) fn load(
+    instance: &Instance,
+    file_path: &Path,
+) -> core::result::Result<Arc<instance::BindingLayout>, CreateBindingLayoutError> {
+    let file = file_path.display().to_string();
+
+    let format = FileFormat::from_path(file_path).ok_or_else(|| {
+        CreateBindingLayoutError::BadFile(format!("{file}: expected a .json or .kdl file"))
+    })?;
+    let text = std::fs::read_to_string(file_path)
+        .map_err(|err| CreateBindingLayoutError::BadFile(format!("{file}: {err}")))?;
+
+    let in_file = |line: usize, error: CreateBindingLayoutError| CreateBindingLayoutError::InFile {
+        file: file.clone(),
+        line,
+        error: Box::new(error),
+    };
+
+    //Each binding along with the line it starts on
+    let (name, interaction_profile, bindings) = match format {
+        FileFormat::Json => {
+            let layout = serde_json::from_str::<JsonBindingLayout>(&text).map_err(|err| {
+                in_file(err.line(), CreateBindingLayoutError::Parse(err.to_string()))
+            })?;
+
+            let bindings = layout
+                .bindings
+                .iter()
+                .map(|raw| {
+                    let line = line_of(&text, raw.get().as_ptr() as usize - text.as_ptr() as usize);
+                    serde_json::from_str::<Binding>(raw.get())
+                        .map(|binding| (line, binding))
+                        .map_err(|err| {
+                            in_file(
+                                line + err.line() - 1,
+                                CreateBindingLayoutError::Parse(err.to_string()),
+                            )
+                        })
+                })
+                .collect::<core::result::Result<Vec<_>, _>>()?;
+
+            let raw = layout.interaction_profile;
+            let line = line_of(&text, raw.get().as_ptr() as usize - text.as_ptr() as usize);
+            let interaction_profile = serde_json::from_str::<String>(raw.get())
+                .map_err(|err| in_file(line, CreateBindingLayoutError::Parse(err.to_string())))?;
+
+            (layout.name, (line, interaction_profile), bindings)
+        }
+        FileFormat::Kdl => {
+            let mut layouts =
+                knuffel::parse::<Vec<KdlBindingLayout>>(&file, &text).map_err(|err| {
+                    in_file(
+                        error_offset(&err).map_or(1, |offset| line_of(&text, offset)),
+                        CreateBindingLayoutError::Parse(format!("{:?}", miette::Report::new(err))),
+                    )
+                })?;
+
+            if layouts.len() != 1 {
+                return Err(CreateBindingLayoutError::BadFile(format!(
+                    "{file}: expected one binding-layout, found {}",
+                    layouts.len()
+                )));
+            }
+            let layout = layouts.remove(0);
+
+            let bindings = layout
+                .bindings
+                .into_iter()
+                .map(|binding| (line_of(&text, binding.span.0), Binding::from(binding)))
+                .collect();
+
+            (
+                layout.name,
+                (line_of(&text, layout.span.0), layout.interaction_profile),
+                bindings,
+            )
+        }
+    };
+
+    let (interaction_profile_line, interaction_profile) = interaction_profile;
+    let interaction_profile = instance.get_path(&interaction_profile).map_err(|_| {
+        in_file(
+            interaction_profile_line,
+            CreateBindingLayoutError::BadInteractionProfilePath(interaction_profile.clone()),
+        )
+    })?;
+
+    let resolved = bindings
+        .iter()
+        .map(|(line, binding)| {
+            resolve_binding(instance, binding).map_err(|error| in_file(*line, error))
+        })
+        .collect::<core::result::Result<Vec<_>, _>>()?;
+
+    if let Err(error) = ProcessedBindingLayout::new(instance, interaction_profile, &resolved) {
+        //Find the binding at fault by processing them one at a time
+        let line = bindings
+            .iter()
+            .zip(&resolved)
+            .find(|(_, binding)| {
+                ProcessedBindingLayout::new(instance, interaction_profile, &[(*binding).clone()])
+                    .is_err()
+            })
+            .map_or(interaction_profile_line, |((line, _), _)| *line);
+
+        return Err(in_file(line, error));
+    }
+
+    instance.create_binding_layout(&name, interaction_profile, &resolved)
+}
+
+/// Resolves the handles of a binding to names
+pub(crate) fn serialize_binding(instance: &Instance, binding: &SuBinding) -> Option<Binding> {
+    let mut chords = Vec::new();
+    let mut inner = binding;
+    while let SuBinding::Chord(chord) = inner {
+        chords.push(Chord {
+            modifier: instance.get_path_string(chord.modifier)?,
+            ty: binding::name(chord.ty),
+            threshold: chord.threshold,
+        });
+        inner = &chord.binding;
+    }
+
+    let action = instance
+        .actions
+        .read()
+        .get((inner.action() as usize).checked_sub(1)?)?
+        .clone();
+    let action_set = action.action_set.upgrade()?;
+
+    let parent_action = match &action.compound {
+        ActionCompoundType::Child { parent, .. } => Some(parent.upgrade()?.name.clone()),
+        _ => None,
+    };
+
+    let mut serial = Binding {
+        action_set: action_set.name.clone(),
+        parent_action,
+        action: action.name.clone(),
+        input_component: instance.get_path_string(inner.path())?,
+        chords,
+        ..Default::default()
+    };
+    serial.set_settings(inner);
+
+    Some(serial)
+}
+
+fn resolve_binding(
+    instance: &Instance,
+    binding: &Binding,
+) -> core::result::Result<SuBinding, CreateBindingLayoutError> {
+    let full_name = match &binding.parent_action {
+        Some(parent_action) => format!("{}/{parent_action}/{}", binding.action_set, binding.action),
+        None => format!("{}/{}", binding.action_set, binding.action),
+    };
+
+    let action = instance
+        .action_sets
+        .read()
+        .iter()
+        .filter(|set| set.name == binding.action_set)
+        .find_map(|set| {
+            set.actions
+                .read()
+                .iter()
+                .find(|candidate| {
+                    candidate.name == binding.action
+                        && has_parent(candidate, binding.parent_action.as_deref())
+                })
+                .map(|action| action.handle)
+        })
+        .ok_or(CreateBindingLayoutError::BadActionName(full_name))?;
+
+    let get_path = |path: &String| {
+        instance
+            .get_path(path)
+            .map_err(|_| CreateBindingLayoutError::BadComponentPath(path.clone()))
+    };
+
+    let mut resolved = binding
+        .to_binding(action, get_path(&binding.input_component)?)
+        .map_err(CreateBindingLayoutError::Parse)?;
+
+    for chord in binding.chords.iter().rev() {
+        resolved = ChordBinding {
+            modifier: get_path(&chord.modifier)?,
+            ty: binding::parse(&chord.ty, "chord type").map_err(CreateBindingLayoutError::Parse)?,
+            threshold: chord.threshold,
+            binding: Box::new(resolved),
+        }
+        .into();
+    }
+
+    Ok(resolved)
+}
+
+fn has_parent(action: &Action, parent_name: Option<&str>) -> bool {
+    match (&action.compound, parent_name) {
+        (ActionCompoundType::Child { parent, .. }, Some(parent_name)) => parent
+            .upgrade()
+            .is_some_and(|parent| parent.name == parent_name),
+        (ActionCompoundType::Child { .. }, None) => false,
+        (_, parent_name) => parent_name.is_none(),
+    }
+}
+
+fn to_kdl(layout: &BindingLayout) -> String {
+    let mut text = String::new();
+
+    writeln!(
+        text,
+        "binding-layout name={} interaction-profile={} {{",
+        string(&layout.name),
+        string(&layout.interaction_profile)
+    )
+    .unwrap();
+
+    for binding in &layout.bindings {
+        let node = kdl_node(
+            "binding",
+            &[string(&binding.kind)],
+            &[
+                ("action-set", Some(string(&binding.action_set))),
+                ("parent", binding.parent_action.as_deref().map(string)),
+                ("action", Some(string(&binding.action))),
+                ("input", Some(string(&binding.input_component))),
+            ],
+        );
+
+        write_kdl_node(&mut text, node, &kdl_settings(binding));
+    }
+
+    text.push_str("}\n");
+    text
+}
+
+/// The chords and settings of the binding as child nodes, in the order they are declared in [`Binding`]
+fn kdl_settings(binding: &Binding) -> Vec<String> {
+    let mut children = binding
+        .chords
+        .iter()
+        .map(|chord| {
+            kdl_node(
+                "chord",
+                &[string(&chord.modifier)],
+                &[
+                    ("type", Some(string(&chord.ty))),
+                    ("threshold", Some(float(chord.threshold))),
+                ],
+            )
+        })
+        .collect::<Vec<_>>();
+
+    if let Some(activator) = &binding.activator {
+        children.push(kdl_node(
+            "activator",
+            &[string(&activator.kind)],
+            &[
+                ("overridden", activator.overridden.as_deref().map(string)),
+                (
+                    "blocking",
+                    activator.blocking.map(|blocking| blocking.to_string()),
+                ),
+                ("impulse", activator.impulse.as_deref().map(string)),
+                (
+                    "duration",
+                    activator.duration.map(|duration| duration.to_string()),
+                ),
+                ("taps", activator.taps.map(|taps| taps.to_string())),
+            ],
+        ));
+    }
+    if let Some(space) = &binding.gyro_space {
+        children.push(kdl_node(
+            "gyro-space",
+            &[string(&space.kind)],
+            &[
+                ("x-axis", space.x_axis.as_deref().map(string)),
+                ("yaw-factor", space.yaw_factor.map(float)),
+                ("roll-factor", space.roll_factor.map(float)),
+                ("relax-angle", space.relax_angle.map(float)),
+            ],
+        ));
+    }
+    if let Some(filtering) = &binding.gyro_filtering {
+        children.push(kdl_node(
+            "gyro-filtering",
+            &[],
+            &[
+                ("cut-off-speed", Some(float(filtering.cut_off_speed))),
+                ("cut-off-recovery", Some(float(filtering.cut_off_recovery))),
+                ("smooth-threshold", Some(float(filtering.smooth_threshold))),
+                ("smooth-time", Some(float(filtering.smooth_time))),
+            ],
+        ));
+    }
+    if let Some(invert) = &binding.invert {
+        children.push(kdl_node(
+            "invert",
+            &[],
+            &[
+                ("x", Some(invert.x.to_string())),
+                ("y", Some(invert.y.to_string())),
+            ],
+        ));
+    }
+    if let Some(processing) = &binding.processing {
+        let mut node = kdl_node(
+            "processing",
+            &[],
+            &[
+                ("shape", Some(string(&processing.shape))),
+                ("inner-deadzone", Some(float(processing.inner_deadzone))),
+                ("outer-deadzone", Some(float(processing.outer_deadzone))),
+                ("anti-deadzone", Some(float(processing.anti_deadzone))),
+                ("curve", Some(string(&processing.curve))),
+                ("exponent", processing.exponent.map(float)),
+            ],
+        );
+        if !processing.points.is_empty() {
+            node.push_str(" {\n");
+            for point in &processing.points {
+                writeln!(
+                    node,
+                    "    point {} {}",
+                    float(point.input),
+                    float(point.output)
+                )
+                .unwrap();
+            }
+            node.push('}');
+        }
+        children.push(node);
+    }
+    if let Some(sensitivity) = &binding.sensitivity {
+        children.push(kdl_node(
+            "sensitivity",
+            &[string(&sensitivity.kind)],
+            &[
+                ("scale", sensitivity.scale.map(float)),
+                ("slow-threshold", sensitivity.slow_threshold.map(float)),
+                ("fast-threshold", sensitivity.fast_threshold.map(float)),
+                ("slow-scale", sensitivity.slow_scale.map(float)),
+                ("fast-scale", sensitivity.fast_scale.map(float)),
+            ],
+        ));
+    }
+    if let Some(edge_acceleration) = &binding.edge_acceleration {
+        children.push(kdl_node(
+            "edge-acceleration",
+            &[],
+            &[
+                ("threshold", Some(float(edge_acceleration.threshold))),
+                ("ramp-time", Some(float(edge_acceleration.ramp_time))),
+                ("multiplier", Some(float(edge_acceleration.multiplier))),
+            ],
+        ));
+    }
+    if let Some(flick_stick) = &binding.flick_stick {
+        children.push(kdl_node(
+            "flick-stick",
+            &[],
+            &[
+                ("flick-time", Some(float(flick_stick.flick_time))),
+                ("threshold", Some(float(flick_stick.threshold))),
+                ("sensitivity", Some(float(flick_stick.sensitivity))),
+            ],
+        ));
+    }
+    if let Some(region) = &binding.stick_region {
+        children.push(kdl_node(
+            "stick-region",
+            &[string(&region.kind)],
+            &[
+                ("direction", region.direction.as_deref().map(string)),
+                ("sectors", region.sectors.as_deref().map(string)),
+                ("deadzone", region.deadzone.map(float)),
+                ("threshold", Some(float(region.threshold))),
+            ],
+        ));
+    }
+    if let Some(stick_axis) = &binding.stick_axis {
+        children.push(kdl_node(
+            "stick-axis",
+            &[string(&stick_axis.axis)],
+            &[("invert", Some(stick_axis.invert.to_string()))],
+        ));
+    }
+    if let Some(trigger) = &binding.trigger {
+        children.push(kdl_node(
+            "trigger",
+            &[],
+            &[
+                ("press", Some(float(trigger.press))),
+                ("release", Some(float(trigger.release))),
+            ],
+        ));
+    }
+    if let Some(touchpad) = &binding.touchpad {
+        children.push(kdl_node(
+            "touchpad",
+            &[],
+            &[
+                ("left", Some(float(touchpad.left))),
+                ("top", Some(float(touchpad.top))),
+                ("right", Some(float(touchpad.right))),
+                ("bottom", Some(float(touchpad.bottom))),
+                ("finger", touchpad.finger.map(|finger| finger.to_string())),
+                ("sensitivity-x", Some(float(touchpad.sensitivity_x))),
+                ("sensitivity-y", Some(float(touchpad.sensitivity_y))),
+            ],
+        ));
+    }
+
+    children
+}
+
+/// A single line node, properties that are None are left out
+fn kdl_node(name: &str, arguments: &[String], properties: &[(&str, Option<String>)]) -> String {
+    let mut node = name.to_owned();
+    for argument in arguments {
+        write!(node, " {argument}").unwrap();
+    }
+    for (property, value) in properties {
+        if let Some(value) = value {
+            write!(node, " {property}={value}").unwrap();
+        }
+    }
+    node
+}
+
+/// Writes a node inside the binding layout, children may span several lines
+fn write_kdl_node(text: &mut String, node: String, children: &[String]) {
+    if children.is_empty() {
+        writeln!(text, "    {node}").unwrap();
+        return;
+    }
+
+    writeln!(text, "    {node} {{").unwrap();
+    for line in children.iter().flat_map(|child| child.lines()) {
+        writeln!(text, "        {line}").unwrap();
+    }
+    text.push_str("    }\n");
+}
+
+/// A quoted KDL string, control characters and newlines are escaped so every value stays on its line
+fn string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+
+    for character in value.chars() {
+        match character {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            '\u{8}' => quoted.push_str("\\b"),
+            '\u{c}' => quoted.push_str("\\f"),
+            character
+                if character.is_control()
+                    || matches!(character, '\u{2028}' | '\u{2029}' | '\u{feff}') =>
+            {
+                write!(quoted, "\\u{{{:x}}}", character as u32).unwrap()
+            }
+            character => quoted.push(character),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+/// Debug formatting always writes a decimal point or exponent so KDL reads the value back as a float,
+/// [`save`] rejects the NaN and infinite values it would write as bare words
+fn float<T: std::fmt::Debug + Into<f64> + Copy>(value: T) -> String {
+    debug_assert!(value.into().is_finite());
+    format!("{value:?}")
+}
+
+/// The byte offset of the first labelled error knuffel reports
+fn error_offset(err: &knuffel::Error) -> Option<usize> {
+    let labels = |diagnostic: &dyn Diagnostic| {
+        diagnostic
+            .labels()
+            .and_then(|mut labels| labels.next())
+            .map(|label| label.offset())
+    };
+
+    labels(err).or_else(|| err.related()?.find_map(labels))
+}
+
+/// The 1-based line that the byte offset falls on
+fn line_of(text: &str, offset: usize) -> usize {
+    text.as_bytes()[..offset.min(text.len())]
+        .iter()
+        .filter(|&&byte| byte == b'\n')
+        .count()
+        + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::{
+        action::ActionTypeEnum,
+        internal::serial::binding::tests::every_kind,
+        runtime::Runtime,
+        types::action_type::{Axis1d, Axis2d, Cursor, Delta2d},
+    };
+
+    fn temp_file(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("suinput_{}_{name}", std::process::id()))
+    }
+
+    fn round_trip(extension: &str) {
+        let runtime = Runtime::new();
+        let instance = runtime.create_instance(None);
+        let action_set = instance.create_action_set("game".into(), 0);
+
+        let mut count = 0;
+        let bindings = every_kind(
+            |ty| {
+                count += 1;
+                let name = format!("action_{count}");
+                let action = match ty {
+                    ActionTypeEnum::Boolean => {
+                        action_set.create_action::<bool>(&name, Default::default())
+                    }
+                    ActionTypeEnum::Axis1d => {
+                        action_set.create_action::<Axis1d>(&name, Default::default())
+                    }
+                    ActionTypeEnum::Axis2d => {
+                        action_set.create_action::<Axis2d>(&name, Default::default())
+                    }
+                    ActionTypeEnum::Delta2d => action_set.create_action::<Delta2d>(&name, ()),
+                    ActionTypeEnum::Cursor => action_set.create_action::<Cursor>(&name, ()),
+                    _ => unreachable!(),
+                };
+                action.handle
+            },
+            |path| instance.get_path(path).unwrap(),
+        );
+
+        let layout = instance
+            .create_binding_layout(
+                "Everything",
+                instance
+                    .get_path("/interaction_profiles/sony/dualsense")
+                    .unwrap(),
+                &bindings,
+            )
+            .unwrap();
+
+        let file_path = temp_file(&format!("round_trip.{extension}"));
+        save(&instance, &layout, &file_path).unwrap();
+        let loaded = load(&instance, &file_path);
+        std::fs::remove_file(&file_path).unwrap();
+        let loaded = loaded.unwrap();
+
+        assert_eq!(loaded.name, layout.name);
+        assert_eq!(loaded.interaction_profile, layout.interaction_profile);
+        assert_eq!(
+            format!("{:?}", loaded.bindings),
+            format!("{:?}", layout.bindings)
+        );
+
+        runtime.destroy();
+    }
+
+    #[test]
+    fn json_round_trip() {
+        round_trip("json");
+    }
+
+    #[test]
+    fn kdl_round_trip() {
+        round_trip("kdl");
+    }
+
+    #[test]
+    fn kdl_string_escapes() {
+        assert_eq!(string("Jump"), r#""Jump""#);
+        assert_eq!(string("say \"hi\"\\"), r#""say \"hi\"\\""#);
+        assert_eq!(string("a\nb\tc\u{8}"), r#""a\nb\tc\b""#);
+        assert_eq!(string("nul\0 bom\u{feff}"), r#""nul\u{0} bom\u{feff}""#);
+    }
+
+    fn error_line(file_name: &str, text: &str) -> usize {
+        let runtime = Runtime::new();
+        let instance = runtime.create_instance(None);
+        instance.create_action_set("game".into(), 0);
+
+        let file_path = temp_file(file_name);
+        std::fs::write(&file_path, text).unwrap();
+        let result = load(&instance, &file_path);
+        std::fs::remove_file(&file_path).unwrap();
+        runtime.destroy();
+
+        match result {
+            Err(CreateBindingLayoutError::InFile { line, .. }) => line,
+            Err(err) => panic!("{err} is not located in the file"),
+            Ok(_) => panic!("{file_name} loaded"),
+        }
+    }
+
+    #[test]
+    fn json_error_lines() {
+        let text = r#"{
+  "name": "Broken",
+
+  "interaction_profile": "interaction_profiles/sony/dualsense",
+  "bindings": []
+}"#;
+        assert_eq!(error_line("bad_profile.json", text), 4);
+    }
+
+    #[test]
+    fn kdl_error_lines() {
+        let text = r#"binding-layout name="Broken" interaction-profile="/interaction_profiles/sony/dualsense" {
+    binding "simple" action-set="game" action="jump" input="/user/gamepad/input/diamond_down/click"
+    binding "simple" action-set="game" action="jump" input=5
+}
+"#;
+        assert_eq!(error_line("bad_syntax.kdl", text), 3);
+    }
+}
@@ -4,6 +4,14 @@ use serde::{Deserialize, Serialize};
 
 use crate::action::ActionTypeEnum;
 
+mod binding;
+mod binding_layout;
+
+pub use binding::Binding;
+pub(crate) use binding_layout::{
+    load as load_binding_layout, save as save_binding_layout, serialize_binding,
+};
+
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub enum ParentActionType {
     StickyBool,
@@ -27,21 +35,12 @@ pub struct ActionSet<'a> {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct BindingLayout<'a> {
-    pub name: &'a str,
-    pub interaction_profile: &'a str,
+pub struct BindingLayout {
+    pub name: String,
+    pub interaction_profile: String,
     pub bindings: Vec<Binding>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub enum Binding {
-    Simple {
-        parent_action: Option<String>,
-        action: String,
-        input_component: String,
-    },
-}
-
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ApplicationInstance<'a> {
     pub name: &'a str,
@@ -49,5 +48,5 @@ pub struct ApplicationInstance<'a> {
     pub unique_id: NonZeroU128,
     pub action_sets: Vec<ActionSet<'a>>,
     pub dynamic_action_sets: Vec<ActionSet<'a>>,
-    pub binding_layouts: Vec<BindingLayout<'a>>,
+    pub binding_layouts: Vec<BindingLayout>,
 }
@@ -4,14 +4,17 @@ use std::{
 };
 
 use flume::Receiver;
+use hashbrown::HashSet;
 
 use log::warn;
 use parking_lot::Mutex;
 
-use suinput_types::event::InputEvent;
+use suinput_types::event::{InputEvent, OutputEvent};
 use thunderdome::{Arena, Index};
 
 use crate::{
+    driver_interface::BatchInputUpdate,
+    internal::types::HashMap,
     runtime::{Driver2RuntimeEvent, Driver2RuntimeEventResponse, Runtime},
     session::Session,
 };
@@ -30,6 +33,7 @@ pub enum WorkerThreadEvent {
     CreateSession {
         handle: Index,
     },
+    Output(OutputEvent),
 }
 
 pub fn spawn_thread(
@@ -46,30 +50,36 @@ pub fn spawn_thread(
         while let Ok(event) = driver2runtime_receiver.recv() {
             match event {
                 WorkerThreadEvent::Driver { id, event } => match event {
-                    Driver2RuntimeEvent::RegisterDevice(ty) => {
-                        worker_thread.register_new_device(id, ty);
+                    Driver2RuntimeEvent::RegisterDevice(ty, identity) => {
+                        worker_thread.register_new_device(id, ty, identity);
                     }
                     Driver2RuntimeEvent::Input(event) => {
                         worker_thread.on_input_event(event);
                     }
-                    Driver2RuntimeEvent::BatchInput(_batch_update) => {
-                        todo!()
+                    Driver2RuntimeEvent::BatchInput(batch_update) => {
+                        worker_thread.on_batch_input_event(batch_update);
                     }
                     Driver2RuntimeEvent::DisconnectDevice(id) => {
-                        let device_idx = Index::from_bits(id).unwrap();
-
-                        worker_thread.device_states.remove(device_idx);
+                        worker_thread.disconnect_device(id);
                     }
                 },
                 WorkerThreadEvent::CreateSession { handle } => {
                     let session = worker_thread.runtime.sessions.read().get(handle).cloned();
                     if let Some(session) = session {
-                        for (device_index, device_state) in worker_thread.device_states.iter() {
+                        for (device_index, device_state) in worker_thread
+                            .device_states
+                            .iter()
+                            .filter(|(idx, _)| !worker_thread.disconnected_devices.contains(idx))
+                        {
                             session
                                 .driver_events_send
                                 .send(Runtime2SessionEvent::RegisterDevice {
                                     idx: device_index,
                                     ty: device_state.ty.clone(),
+                                    driver: *worker_thread
+                                        .device_drivers
+                                        .get(&device_index)
+                                        .unwrap(),
                                 })
                                 .unwrap();
                         }
@@ -82,6 +92,9 @@ pub fn spawn_thread(
                         )
                     }
                 }
+                WorkerThreadEvent::Output(event) => {
+                    worker_thread.on_output_event(event);
+                }
             }
         }
     })
@@ -91,6 +104,11 @@ struct WorkerThread {
     runtime: Arc<Runtime>,
     sessions: ParallelArena<Arc<Session>>,
     device_states: Arena<DeviceState>,
+    //Devices registered with an identity are kept around after disconnecting so they can be reused
+    device_identities: HashMap<(DevicePath, String), Index>,
+    disconnected_devices: HashSet<Index>,
+    //The driver each device was registered by, used for routing output events
+    device_drivers: HashMap<Index, usize>,
 }
 
 impl WorkerThread {
@@ -99,16 +117,41 @@ impl WorkerThread {
             runtime: runtime.upgrade().unwrap(),
             sessions: ParallelArena::new(),
             device_states: Arena::new(),
+            device_identities: HashMap::new(),
+            disconnected_devices: HashSet::new(),
+            device_drivers: HashMap::new(),
         }
     }
 
-    fn register_new_device(&mut self, driver_id: usize, ty: DevicePath) {
+    fn register_new_device(&mut self, driver_id: usize, ty: DevicePath, identity: Option<String>) {
         let device_type = self.runtime.device_types.get(ty).unwrap();
 
-        //TODO: Device ID persistence
-        let device_id = self
-            .device_states
-            .insert(DeviceState::new(device_type.clone()));
+        let device_id = match identity {
+            Some(identity) => match self.device_identities.get(&(ty, identity.clone())) {
+                Some(device_id) if self.disconnected_devices.remove(device_id) => *device_id,
+                Some(_) => {
+                    //Sharing the id would merge the inputs of two physical devices
+                    warn!(
+                        "Device {:?} registered twice without disconnecting, giving it a new id",
+                        identity
+                    );
+                    self.device_states
+                        .insert(DeviceState::new(device_type.clone()))
+                }
+                None => {
+                    let device_id = self
+                        .device_states
+                        .insert(DeviceState::new(device_type.clone()));
+                    self.device_identities.insert((ty, identity), device_id);
+                    device_id
+                }
+            },
+            None => self
+                .device_states
+                .insert(DeviceState::new(device_type.clone())),
+        };
+
+        self.device_drivers.insert(device_id, driver_id);
 
         self.runtime
             .driver_response_senders
@@ -124,11 +167,39 @@ impl WorkerThread {
                 .send(Runtime2SessionEvent::RegisterDevice {
                     idx: device_id,
                     ty: device_type.clone(),
+                    driver: driver_id,
                 })
                 .unwrap();
         }
     }
 
+    fn disconnect_device(&mut self, id: u64) {
+        let device_idx = Index::from_bits(id).unwrap();
+
+        if self
+            .device_identities
+            .values()
+            .any(|idx| *idx == device_idx)
+        {
+            if !self.disconnected_devices.insert(device_idx) {
+                warn!("Driver disconnected device {:?} twice", device_idx);
+                return;
+            }
+        } else if self.device_states.remove(device_idx).is_none() {
+            warn!("Driver disconnected unknown device {:?}", device_idx);
+            return;
+        } else {
+            self.device_drivers.remove(&device_idx);
+        }
+
+        for session in self.sessions.iter() {
+            session
+                .driver_events_send
+                .send(Runtime2SessionEvent::DisconnectDevice { idx: device_idx })
+                .unwrap();
+        }
+    }
+
     fn on_input_event(&mut self, event: InputEvent) {
         let device_idx = Index::from_bits(event.device).unwrap();
         self.device_states.get(device_idx).unwrap();
@@ -140,4 +211,31 @@ impl WorkerThread {
                 .unwrap();
         }
     }
+
+    fn on_batch_input_event(&mut self, batch_update: BatchInputUpdate) {
+        let device_idx = Index::from_bits(batch_update.device).unwrap();
+        self.device_states.get(device_idx).unwrap();
+
+        //Each session receives the whole batch in a single message so it is always applied within one sync
+        for session in self.sessions.iter() {
+            session
+                .driver_events_send
+                .send(Runtime2SessionEvent::BatchInput(batch_update.clone()))
+                .unwrap();
+        }
+    }
+
+    fn on_output_event(&mut self, event: OutputEvent) {
+        let device_idx = Index::from_bits(event.device).unwrap();
+
+        //Outputs fired before a session processed the disconnect are dropped
+        if self.disconnected_devices.contains(&device_idx) {
+            return;
+        }
+
+        match self.device_drivers.get(&device_idx) {
+            Some(driver) => self.runtime.send_output_event(*driver, event),
+            None => warn!("Output event sent to unknown device {:?}", device_idx),
+        }
+    }
 }
@@ -4,7 +4,7 @@ use std::{
     path::Path,
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc,
+        Arc, Weak,
     },
     thread::JoinHandle,
     time::{Duration, Instant},
@@ -12,12 +12,14 @@ use std::{
 
 use flume::Sender;
 use itertools::Itertools;
+use miette::Diagnostic;
 use parking_lot::{Mutex, RwLock};
 
 use suinput_types::{
-    controller_paths::GameControllerPaths, event::InputEvent, event::PathFormatError,
-    keyboard::KeyboardPaths, SuPath,
+    event::InputComponentEvent, event::InputEvent, event::OutputEvent, event::PathFormatError,
+    keyboard::KeyboardPaths, SuPath, Time,
 };
+use thiserror::Error;
 use thunderdome::Arena;
 
 use crate::{
@@ -33,10 +35,34 @@ use crate::{
 
 use super::instance::Instance;
 
+/// Additional configuration files loaded alongside the runtime's built-in configs
+#[derive(Debug, Default, Clone)]
+pub struct RuntimeConfig {
+    /// KDL device files, loaded in order after the scanned directories
+    pub device_files: Vec<PathBuf>,
+    /// Directories scanned for `.kdl` device files, loaded in file name order
+    pub device_directories: Vec<PathBuf>,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum RuntimeConfigError {
+    #[error("Could not read `{}`", .0.display())]
+    Io(PathBuf, #[source] std::io::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Parse(#[from] knuffel::Error),
+    #[error("Bad Path `{0}`")]
+    BadPath(String),
+    #[error("Device `{0}` has unknown parent `{1}`")]
+    UnknownParent(String, String),
+    #[error("Device `{0}` inherits from itself")]
+    InheritanceCycle(String),
+    #[error("Interaction profile `{0}` uses unknown device type `{1}`")]
+    UnknownDeviceType(String, String),
+}
+
 pub struct Runtime {
     pub(crate) paths: Arc<PathManager>,
-    pub(crate) common_paths: CommonPaths,
-    pub(crate) controller_paths: GameControllerPaths,
     pub(crate) device_types: DeviceTypes,
     pub(crate) interaction_profile_types: InteractionProfileTypes,
 
@@ -44,23 +70,29 @@ pub struct Runtime {
     _thread: JoinHandle<()>,
     pub(crate) driver_response_senders: Mutex<Vec<Sender<Driver2RuntimeEventResponse>>>,
     drivers: RwLock<Vec<Box<dyn SuInputDriver>>>,
+    //Output events for a driver that is still initializing, see add_driver
+    pending_output_events: Mutex<Vec<(usize, OutputEvent)>>,
 
     pub(crate) instances: RwLock<Vec<Arc<Instance>>>,
     pub(crate) sessions: RwLock<Arena<Arc<Session>>>,
+
+    start_time: Instant,
 }
 
 impl Runtime {
     pub fn new() -> Arc<Self> {
+        Self::with_config(RuntimeConfig::default()).unwrap()
+    }
+
+    pub fn with_config(config: RuntimeConfig) -> Result<Arc<Self>, RuntimeConfigError> {
         let (worker_thread_sender, worker_thread_receiver) = flume::bounded(100);
 
         let paths = Arc::new(PathManager::new());
 
         let common_paths = CommonPaths::new(|str| paths.get_path(str).unwrap());
         let keyboard_paths = KeyboardPaths::new(|str| paths.get_path(str).unwrap());
-        let controller_paths = GameControllerPaths::new(|str| paths.get_path(str).unwrap());
-        let device_types = DeviceTypes::new(&common_paths, &keyboard_paths, &paths);
-        let interaction_profile_types =
-            InteractionProfileTypes::new(&device_types, |str| paths.get_path(str).unwrap());
+        let device_types = DeviceTypes::new(&common_paths, &keyboard_paths, &paths, &config)?;
+        let interaction_profile_types = InteractionProfileTypes::new(&device_types, &paths)?;
 
         let ready = Arc::new(Mutex::new(()));
         let lock = ready.lock();
@@ -74,21 +106,21 @@ impl Runtime {
                 ready.clone(),
             ),
             drivers: Default::default(),
+            pending_output_events: Default::default(),
             driver_response_senders: Default::default(),
             instances: Default::default(),
             device_types,
-            common_paths,
             interaction_profile_types,
             sessions: Default::default(),
-            controller_paths,
+            start_time: Instant::now(),
         });
 
         std::mem::drop(lock);
 
-        runtime
+        Ok(runtime)
     }
 
-    pub fn add_driver<F, T, E>(&self, f: F) -> Result<usize, E>
+    pub fn add_driver<F, T, E>(self: &Arc<Self>, f: F) -> Result<usize, E>
     where
         F: FnOnce(RuntimeInterface) -> Result<T, E>,
         T: SuInputDriver + 'static,
@@ -99,24 +131,33 @@ impl Runtime {
 
         let runtime_interface = Arc::new(EmbeddedDriverRuntimeInterface {
             ready: AtomicBool::new(false),
+            runtime: Arc::downgrade(self),
             paths: self.paths.clone(),
             sender: self.worker_thread_sender.clone(),
             idx,
             receiver: runtime2driver_receiver,
         });
 
-        let driver = f(RuntimeInterface(runtime_interface.clone()))?;
+        let mut driver = f(RuntimeInterface(runtime_interface.clone()))?;
 
         self.driver_response_senders
             .lock()
             .push(runtime2driver_sender);
 
-        {
-            let mut drivers = self.drivers.write();
-            drivers.push(Box::new(driver));
-            runtime_interface.ready.store(true, Ordering::Relaxed);
-            drivers.get_mut(idx).unwrap().initialize();
-        }
+        //Initializing may wait on the worker thread, which must not be blocked on the drivers lock in the meantime
+        runtime_interface.ready.store(true, Ordering::Relaxed);
+        driver.initialize();
+
+        let mut drivers = self.drivers.write();
+        drivers.push(Box::new(driver));
+
+        //Devices registered while initializing may already have been sent output events
+        self.pending_output_events.lock().retain(|(driver, event)| {
+            if *driver == idx {
+                drivers[idx].handle_output_event(*event);
+            }
+            *driver != idx
+        });
 
         Ok(idx)
     }
@@ -132,6 +173,36 @@ impl Runtime {
         instance
     }
 
+    /// Converts an Instant into the runtime's Time (nanoseconds since the runtime was created)
+    pub fn get_time(&self, instant: Instant) -> Time {
+        Time(
+            instant
+                .saturating_duration_since(self.start_time)
+                .as_nanos() as u64,
+        )
+    }
+
+    pub(crate) fn get_component_state(
+        &self,
+        driver: usize,
+        device: u64,
+        path: SuPath,
+    ) -> Option<InputComponentEvent> {
+        self.drivers
+            .read()
+            .get(driver)
+            .and_then(|driver| driver.get_component_state(device, path))
+    }
+
+    pub(crate) fn send_output_event(&self, driver: usize, event: OutputEvent) {
+        let drivers = self.drivers.read();
+        match drivers.get(driver) {
+            Some(driver) => driver.handle_output_event(event),
+            //Held until add_driver finishes so it isn't lost
+            None => self.pending_output_events.lock().push((driver, event)),
+        }
+    }
+
     pub fn destroy(&self) {
         for driver in self.drivers.write().iter_mut() {
             driver.destroy()
@@ -145,6 +216,7 @@ impl Runtime {
 #[derive(Debug)]
 pub struct EmbeddedDriverRuntimeInterface {
     ready: AtomicBool,
+    runtime: Weak<Runtime>,
     paths: Arc<PathManager>,
     sender: flume::Sender<worker_thread::WorkerThreadEvent>,
     receiver: flume::Receiver<Driver2RuntimeEventResponse>,
@@ -152,15 +224,30 @@ pub struct EmbeddedDriverRuntimeInterface {
 }
 
 impl RuntimeInterfaceTrait for EmbeddedDriverRuntimeInterface {
-    fn register_new_device(&self, device_type: SuPath) -> Result<u64, RuntimeInterfaceError> {
+    fn register_new_device(
+        &self,
+        device_type: SuPath,
+        identity: Option<&str>,
+    ) -> Result<u64, RuntimeInterfaceError> {
         if !self.ready.load(Ordering::Relaxed) {
             return Err(RuntimeInterfaceError::DriverUninitialized);
         }
 
+        let runtime = self.runtime.upgrade().unwrap();
+        if runtime.device_types.get(device_type).is_none() {
+            return Err(self.paths.get_path_string(device_type).map_or(
+                RuntimeInterfaceError::InvalidPathHandle(device_type),
+                RuntimeInterfaceError::UnknownDeviceType,
+            ));
+        }
+
         self.sender
             .send(WorkerThreadEvent::Driver {
                 id: self.idx,
-                event: Driver2RuntimeEvent::RegisterDevice(device_type),
+                event: Driver2RuntimeEvent::RegisterDevice(
+                    device_type,
+                    identity.map(str::to_owned),
+                ),
             })
             .unwrap();
 
@@ -187,6 +274,26 @@ impl RuntimeInterfaceTrait for EmbeddedDriverRuntimeInterface {
         Ok(())
     }
 
+    fn register_device_type(
+        &self,
+        device_type: DeviceTypeInfo,
+    ) -> Result<(), RuntimeInterfaceError> {
+        let runtime = self.runtime.upgrade().unwrap();
+        runtime.device_types.register(device_type, &self.paths)
+    }
+
+    fn register_interaction_profile(
+        &self,
+        interaction_profile: InteractionProfileInfo,
+    ) -> Result<(), RuntimeInterfaceError> {
+        let runtime = self.runtime.upgrade().unwrap();
+        runtime.interaction_profile_types.register(
+            interaction_profile,
+            &runtime.device_types,
+            &self.paths,
+        )
+    }
+
     fn send_component_event(
         &self,
         component_event: InputEvent,
@@ -242,7 +349,7 @@ pub(crate) enum Driver2RuntimeEventResponse {
 
 #[derive(Debug, Clone)]
 pub enum Driver2RuntimeEvent {
-    RegisterDevice(SuPath),
+    RegisterDevice(SuPath, Option<String>),
     DisconnectDevice(u64),
     Input(InputEvent),
     BatchInput(BatchInputUpdate),
@@ -1,17 +1,16 @@
-use std::{
-    num::NonZeroUsize,
-    sync::{Arc, Weak},
-};
+use std::sync::{Arc, Weak};
 
 use flume::{Receiver, Sender};
 use parking_lot::{Mutex, RwLock};
-use suinput_types::action::ActionListener;
+use suinput_types::{action::ActionListener, event::OutputComponentEvent, SuPath, WindowHandle};
 
+pub use crate::internal::input_component::InputComponentState;
 use crate::types::action_type::ActionType;
 use crate::{
     action::Action,
     action_set::ActionSet,
     application_instance::ApplicationInstance,
+    instance::BindingLayout,
     internal::inner_session::{InnerSession, Runtime2SessionEvent, SessionActionEvent},
     runtime::Runtime,
     user::User,
@@ -62,6 +61,53 @@ impl Session {
             .unwrap();
     }
 
+    /// Sent to the bound output components during the next sync
+    pub fn fire_output_action(&self, action: &Action, event: OutputComponentEvent) {
+        self.action_events
+            .0
+            .send(SessionActionEvent::Output {
+                action: action.handle,
+                event,
+            })
+            .unwrap();
+    }
+
+    /// Replaces the binding layout of its interaction profile during the next sync
+    ///
+    /// Actions held by the old binding layout are released and inputs that are still held are re-evaluated against the new one
+    pub fn set_binding_layout(&self, binding_layout: &BindingLayout) {
+        self.user.new_binding_layouts.lock().insert(
+            binding_layout.interaction_profile,
+            Some(binding_layout.processed_cache.clone()),
+        );
+    }
+
+    /// Removes the binding layout of the interaction profile during the next sync, releasing any actions it held
+    pub fn remove_binding_layout(&self, interaction_profile: SuPath) {
+        self.user
+            .new_binding_layouts
+            .lock()
+            .insert(interaction_profile, None);
+    }
+
+    /// Cursor actions only follow the cursor while it is over this window
+    pub fn set_window(&self, window: Option<WindowHandle>) {
+        self.inner.lock().user.window = window;
+    }
+
+    /// The aggregated state of an input component as of the last sync, e.g. `/user/gamepad` + `/input/trigger_left/value`
+    pub fn get_input_component_state(
+        &self,
+        user_path: SuPath,
+        input_path: SuPath,
+    ) -> Option<InputComponentState> {
+        self.inner.lock().get_input_component_state(
+            &self.runtime.upgrade().unwrap(),
+            user_path,
+            input_path,
+        )
+    }
+
     pub fn get_action_state<T: ActionType>(&self, action: &Action) -> Result<T::State, ()> {
         let action_states = self.user.action_states.read();
 
@@ -4,6 +4,7 @@ use mint::Vector2;
 
 use suinput_types::{
     action::{ActionStateEnum, ChildActionType},
+    event::OutputComponentEvent,
     Time,
 };
 
@@ -47,8 +48,11 @@ pub(crate) mod private {
     impl Sealed for bool {}
     impl Sealed for Value {}
     impl Sealed for Delta2d {}
+    impl Sealed for Cursor {}
     impl Sealed for Axis1d {}
     impl Sealed for Axis2d {}
+    impl Sealed for Pose {}
+    impl Sealed for Output {}
 
     //Used for putting private methods on ActionType
     pub trait InternalActionType: ActionType {
@@ -247,6 +251,52 @@ impl InternalActionType for Delta2d {
     }
 }
 
+/// The system cursor or a pointer within the session's window
+#[derive(Debug, Clone, Copy)]
+pub struct Cursor;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CursorActionState {
+    /// None until the cursor has been over the session's window
+    pub current_state: Option<suinput_types::event::Cursor>,
+    pub last_changed_time: Time,
+    pub is_active: bool,
+}
+
+impl ActionType for Cursor {
+    type Value = suinput_types::event::Cursor;
+    type State = CursorActionState;
+    type CreateInfo = ();
+
+    fn from_ase(ase: &ActionStateEnum) -> Option<Self::Value> {
+        match ase {
+            ActionStateEnum::Cursor(state) => *state,
+            _ => None,
+        }
+    }
+
+    fn pick_state(state: &OutActionStateEnum) -> Option<&Self::State> {
+        match state {
+            OutActionStateEnum::Cursor(state) => Some(state),
+            _ => None,
+        }
+    }
+
+    type Internal = Self;
+
+    fn appease_the_type_checker(
+        create_info: Self::CreateInfo,
+    ) -> <<Self as ActionType>::Internal as ActionType>::CreateInfo {
+        create_info
+    }
+}
+
+impl InternalActionType for Cursor {
+    fn action_type() -> ActionTypeEnum {
+        ActionTypeEnum::Cursor
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Axis1d;
 
@@ -438,13 +488,81 @@ impl InternalActionType for Axis2d {
     }
 }
 
+/// Fired by the app through [`Session::fire_output_action`](crate::session::Session::fire_output_action)
+/// and sent to whichever output components the action is bound to
 #[derive(Debug, Clone, Copy)]
-pub struct Pose {
-    transform: mint::Vector3<f32>,
-    oritentation: mint::Quaternion<f32>,
+pub struct Output;
+
+impl ActionType for Output {
+    type Value = OutputComponentEvent;
+    type State = ();
+    type CreateInfo = ();
+
+    fn from_ase(_: &ActionStateEnum) -> Option<Self::Value> {
+        None
+    }
+
+    fn pick_state(state: &OutActionStateEnum) -> Option<&Self::State> {
+        match state {
+            OutActionStateEnum::Output => Some(&()),
+            _ => None,
+        }
+    }
+
+    type Internal = Self;
+
+    fn appease_the_type_checker(
+        create_info: Self::CreateInfo,
+    ) -> <<Self as ActionType>::Internal as ActionType>::CreateInfo {
+        create_info
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
+impl InternalActionType for Output {
+    fn action_type() -> ActionTypeEnum {
+        ActionTypeEnum::Output
+    }
+}
+
+pub use suinput_types::action::Pose;
+
+#[derive(Debug, Clone, Copy, Default)]
 pub struct PoseActionState {
+    pub current_state: Pose,
+    pub last_changed_time: Time,
     pub is_active: bool,
 }
+
+impl ActionType for Pose {
+    type Value = Self;
+    type State = PoseActionState;
+    type CreateInfo = ();
+
+    fn from_ase(ase: &ActionStateEnum) -> Option<Self::Value> {
+        match ase {
+            ActionStateEnum::Pose(state) => Some(*state),
+            _ => None,
+        }
+    }
+
+    fn pick_state(state: &OutActionStateEnum) -> Option<&Self::State> {
+        match state {
+            OutActionStateEnum::Pose(state) => Some(state),
+            _ => None,
+        }
+    }
+
+    type Internal = Self;
+
+    fn appease_the_type_checker(
+        create_info: Self::CreateInfo,
+    ) -> <<Self as ActionType>::Internal as ActionType>::CreateInfo {
+        create_info
+    }
+}
+
+impl InternalActionType for Pose {
+    fn action_type() -> ActionTypeEnum {
+        ActionTypeEnum::Pose
+    }
+}
@@ -6,22 +6,27 @@ use crate::action::ActionTypeEnum;
 use crate::internal::binding::binding_engine::processed_binding_layout::ProcessedBindingLayout;
 use crate::internal::types::HashMap;
 use crate::types::action_type::{
-    Axis1dActionState, Axis2dActionState, BooleanActionState, Delta2dActionState, ValueActionState,
+    Axis1dActionState, Axis2dActionState, BooleanActionState, CursorActionState,
+    Delta2dActionState, PoseActionState, ValueActionState,
 };
 
 #[derive(Default)]
 pub struct User {
     pub action_states: RwLock<HashMap<u64, OutActionStateEnum>>,
-    //should there also be a way to remove binding layouts?
-    pub new_binding_layouts: Mutex<HashMap<SuPath, ProcessedBindingLayout>>,
+    /// Attached during the next sync, None removes the interaction profile's binding layout
+    pub new_binding_layouts: Mutex<HashMap<SuPath, Option<ProcessedBindingLayout>>>,
 }
 
 pub enum OutActionStateEnum {
     Boolean(BooleanActionState),
     Delta2d(Delta2dActionState),
+    Cursor(CursorActionState),
     Value(ValueActionState),
     Axis1d(Axis1dActionState),
     Axis2d(Axis2dActionState),
+    Pose(PoseActionState),
+    //Output actions are fired by the app and have no state
+    Output,
 }
 
 impl OutActionStateEnum {
@@ -29,9 +34,12 @@ impl OutActionStateEnum {
         match action_state {
             ActionTypeEnum::Boolean => Self::Boolean(Default::default()),
             ActionTypeEnum::Delta2d => Self::Delta2d(Default::default()),
+            ActionTypeEnum::Cursor => Self::Cursor(Default::default()),
             ActionTypeEnum::Value => Self::Value(Default::default()),
             ActionTypeEnum::Axis1d => Self::Axis1d(Default::default()),
             ActionTypeEnum::Axis2d => Self::Axis2d(Default::default()),
+            ActionTypeEnum::Pose => Self::Pose(Default::default()),
+            ActionTypeEnum::Output => Self::Output,
         }
     }
 
@@ -49,6 +57,11 @@ impl OutActionStateEnum {
                 prev_state.accumulated_delta = *new_value;
                 prev_state.last_changed_time = last_changed_time;
             }
+            (OutActionStateEnum::Cursor(prev_state), ActionStateEnum::Cursor(new_value)) => {
+                prev_state.is_active = true;
+                prev_state.current_state = *new_value;
+                prev_state.last_changed_time = last_changed_time;
+            }
             (OutActionStateEnum::Value(prev_state), ActionStateEnum::Value(new_value)) => {
                 let old_value = prev_state.current_state;
                 prev_state.is_active = true;
@@ -70,6 +83,11 @@ impl OutActionStateEnum {
                 prev_state.changed_since_last_sync = *new_value != old_value;
                 prev_state.last_changed_time = last_changed_time;
             }
+            (OutActionStateEnum::Pose(prev_state), ActionStateEnum::Pose(new_value)) => {
+                prev_state.is_active = true;
+                prev_state.current_state = *new_value;
+                prev_state.last_changed_time = last_changed_time;
+            }
             _ => panic!("Action Type Mismatch"),
         }
     }
@@ -78,9 +96,12 @@ impl OutActionStateEnum {
         match self {
             OutActionStateEnum::Boolean(prev_state) => *prev_state = Default::default(),
             OutActionStateEnum::Delta2d(prev_state) => *prev_state = Default::default(),
+            OutActionStateEnum::Cursor(prev_state) => *prev_state = Default::default(),
             OutActionStateEnum::Value(prev_state) => *prev_state = Default::default(),
             OutActionStateEnum::Axis1d(prev_state) => *prev_state = Default::default(),
             OutActionStateEnum::Axis2d(prev_state) => *prev_state = Default::default(),
+            OutActionStateEnum::Pose(prev_state) => *prev_state = Default::default(),
+            OutActionStateEnum::Output => (),
         }
     }
 }
@@ -1,6 +1,8 @@
 use std::time::Instant;
 
-use mint::Vector2;
+use mint::{Quaternion, Vector2, Vector3};
+
+use crate::{event::Cursor, WindowHandle};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ActionCreateInfo {
@@ -46,6 +48,7 @@ pub enum ActionEventEnum {
     },
     Cursor {
         normalized_window_coords: Vector2<f64>,
+        window: WindowHandle,
     },
     Value {
         state: f32,
@@ -56,16 +59,44 @@ pub enum ActionEventEnum {
     Axis2d {
         state: Vector2<f32>,
     },
+    Pose {
+        pose: Pose,
+    },
 }
 
 #[derive(Debug, Clone, Copy)]
 pub enum ActionStateEnum {
     Boolean(bool),
     Delta2d(Vector2<f64>),
-    Cursor(Vector2<f64>),
+    ///None until the cursor has been over the session's window
+    Cursor(Option<Cursor>),
     Value(f32),
     Axis1d(f32),
     Axis2d(Vector2<f32>),
+    Pose(Pose),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pose {
+    pub orientation: Quaternion<f32>,
+    ///None if the source only tracks orientation
+    pub position: Option<Vector3<f32>>,
+}
+
+impl Default for Pose {
+    fn default() -> Self {
+        Self {
+            orientation: Quaternion {
+                v: Vector3 {
+                    x: 0.,
+                    y: 0.,
+                    z: 0.,
+                },
+                s: 1.,
+            },
+            position: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
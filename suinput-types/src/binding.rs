@@ -6,7 +6,7 @@ pub struct SimpleBinding {
     pub path: SuPath,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy)]
 pub struct BooleanBinding {
     pub input: SuPath,
 
@@ -15,45 +15,310 @@ pub struct BooleanBinding {
     pub output: u64,
 }
 
-mod activator {
-    #[derive(Debug, Clone, Copy)]
+///Gates a binding on the state of a modifier input on the same interaction profile
+#[derive(Debug, Clone)]
+pub struct ChordBinding {
+    pub modifier: SuPath,
+
+    pub ty: chord::ChordType,
+    ///How far a trigger modifier has to be pulled to count as held, from 0 to 1, ignored for buttons
+    pub threshold: f32,
+
+    pub binding: Box<Binding>,
+}
+
+///Binds a gyro to a delta2d action
+#[derive(Debug, Clone, Copy)]
+pub struct GyroBinding {
+    pub input: SuPath,
+
+    pub space: gyro::GyroSpace,
+    pub filtering: gyro::GyroFiltering,
+    pub sensitivity: Sensitivity<f32>,
+    pub invert_x: bool,
+    pub invert_y: bool,
+
+    pub output: u64,
+}
+
+///Binds a joystick to an axis2d action
+#[derive(Debug, Clone)]
+pub struct JoystickBinding {
+    pub input: SuPath,
+
+    pub processing: joystick::JoystickProcessing,
+
+    pub output: u64,
+}
+
+///Binds a joystick to a delta2d action as a flick stick
+///
+///Pushing the stick to the edge turns the camera to face the stick's direction and rotating the stick while it is held turns the camera by the same angle
+#[derive(Debug, Clone, Copy)]
+pub struct FlickStickBinding {
+    pub input: SuPath,
+
+    ///Seconds a flick takes to reach the stick's direction
+    pub flick_time: f32,
+    ///How far the stick has to be pushed to flick, from 0 to 1
+    pub threshold: f32,
+    ///Output units per degree of yaw
+    pub sensitivity: f64,
+
+    pub output: u64,
+}
+
+///Binds a joystick to a delta2d action as a camera stick
+///
+///The output is in degrees scaled by the time between updates, with up being negative like mouse movement
+#[derive(Debug, Clone)]
+pub struct StickCameraBinding {
+    pub input: SuPath,
+
+    pub processing: joystick::JoystickProcessing,
+    ///Degrees per second, scaled by how far the stick is pushed
+    pub sensitivity: Sensitivity<f32>,
+    pub edge_acceleration: Option<joystick::EdgeAcceleration>,
+
+    pub output: u64,
+}
+
+///Binds a single axis of a joystick to an axis1d action
+#[derive(Debug, Clone)]
+pub struct StickAxisBinding {
+    pub input: SuPath,
+
+    pub axis: joystick::StickAxis,
+    ///Deadzones are applied to the whole stick before the axis is taken
+    pub processing: joystick::JoystickProcessing,
+    pub invert: bool,
+
+    pub output: u64,
+}
+
+///Binds a region of a joystick to a boolean action
+#[derive(Debug, Clone, Copy)]
+pub struct StickRegionBinding {
+    pub input: SuPath,
+
+    pub region: joystick::StickRegion,
+
+    pub output: u64,
+}
+
+///Binds a trigger to a boolean action
+///
+///The action is pressed once the trigger goes above `press_threshold` and released once it drops to `release_threshold`
+#[derive(Debug, Clone, Copy)]
+pub struct TriggerBinding {
+    pub input: SuPath,
+
+    pub press_threshold: f32,
+    pub release_threshold: f32,
+
+    pub output: u64,
+}
+
+///Binds a touchpad to a boolean, axis2d, cursor or delta2d action
+///
+///Only fingers that touch down inside the region are used, positions are relative to the region
+#[derive(Debug, Clone, Copy)]
+pub struct TouchpadBinding {
+    pub input: SuPath,
+
+    pub region: touchpad::TouchpadRegion,
+    ///Only follow this touch point, any finger is used if None
+    pub finger: Option<usize>,
+    ///Multiplies the movement of delta2d actions, in region widths and heights
+    pub sensitivity: (f64, f64),
+
+    pub output: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sensitivity<T> {
+    Linear(T),
+    ///Scales between `slow_scale` and `fast_scale` as the input speed goes from `slow_threshold` to `fast_threshold`
+    Acceleration {
+        slow_threshold: T,
+        fast_threshold: T,
+        slow_scale: T,
+        fast_scale: T,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub enum Binding {
+    Simple(SimpleBinding),
+    Boolean(BooleanBinding),
+    Gyro(GyroBinding),
+    Joystick(JoystickBinding),
+    FlickStick(FlickStickBinding),
+    StickCamera(StickCameraBinding),
+    StickRegion(StickRegionBinding),
+    StickAxis(StickAxisBinding),
+    Trigger(TriggerBinding),
+    Touchpad(TouchpadBinding),
+    ///Chords can be nested to require several modifiers
+    Chord(ChordBinding),
+}
+
+impl Binding {
+    pub fn action(&self) -> u64 {
+        match self {
+            Binding::Simple(binding) => binding.action,
+            Binding::Boolean(binding) => binding.output,
+            Binding::Gyro(binding) => binding.output,
+            Binding::Joystick(binding) => binding.output,
+            Binding::FlickStick(binding) => binding.output,
+            Binding::StickCamera(binding) => binding.output,
+            Binding::StickRegion(binding) => binding.output,
+            Binding::StickAxis(binding) => binding.output,
+            Binding::Trigger(binding) => binding.output,
+            Binding::Touchpad(binding) => binding.output,
+            Binding::Chord(binding) => binding.binding.action(),
+        }
+    }
+
+    pub fn path(&self) -> SuPath {
+        match self {
+            Binding::Simple(binding) => binding.path,
+            Binding::Boolean(binding) => binding.input,
+            Binding::Gyro(binding) => binding.input,
+            Binding::Joystick(binding) => binding.input,
+            Binding::FlickStick(binding) => binding.input,
+            Binding::StickCamera(binding) => binding.input,
+            Binding::StickRegion(binding) => binding.input,
+            Binding::StickAxis(binding) => binding.input,
+            Binding::Trigger(binding) => binding.input,
+            Binding::Touchpad(binding) => binding.input,
+            Binding::Chord(binding) => binding.binding.path(),
+        }
+    }
+}
+
+impl From<SimpleBinding> for Binding {
+    fn from(binding: SimpleBinding) -> Self {
+        Binding::Simple(binding)
+    }
+}
+
+impl From<BooleanBinding> for Binding {
+    fn from(binding: BooleanBinding) -> Self {
+        Binding::Boolean(binding)
+    }
+}
+
+impl From<GyroBinding> for Binding {
+    fn from(binding: GyroBinding) -> Self {
+        Binding::Gyro(binding)
+    }
+}
+
+impl From<JoystickBinding> for Binding {
+    fn from(binding: JoystickBinding) -> Self {
+        Binding::Joystick(binding)
+    }
+}
+
+impl From<FlickStickBinding> for Binding {
+    fn from(binding: FlickStickBinding) -> Self {
+        Binding::FlickStick(binding)
+    }
+}
+
+impl From<StickCameraBinding> for Binding {
+    fn from(binding: StickCameraBinding) -> Self {
+        Binding::StickCamera(binding)
+    }
+}
+
+impl From<StickRegionBinding> for Binding {
+    fn from(binding: StickRegionBinding) -> Self {
+        Binding::StickRegion(binding)
+    }
+}
+
+impl From<StickAxisBinding> for Binding {
+    fn from(binding: StickAxisBinding) -> Self {
+        Binding::StickAxis(binding)
+    }
+}
+
+impl From<TriggerBinding> for Binding {
+    fn from(binding: TriggerBinding) -> Self {
+        Binding::Trigger(binding)
+    }
+}
+
+impl From<TouchpadBinding> for Binding {
+    fn from(binding: TouchpadBinding) -> Self {
+        Binding::Touchpad(binding)
+    }
+}
+
+impl From<ChordBinding> for Binding {
+    fn from(binding: ChordBinding) -> Self {
+        Binding::Chord(binding)
+    }
+}
+
+///Durations are in milliseconds
+pub mod activator {
+    ///What happens to an activator when another activator on the same input fires
+    #[derive(
+        Debug, Clone, Copy, PartialEq, Eq, strum_macros::EnumString, strum_macros::IntoStaticStr,
+    )]
+    #[strum(serialize_all = "kebab-case")]
     pub enum OverriddenBehavior {
         Block,
         Interrupt,
         None,
     }
 
-    #[derive(Debug, Clone, Copy)]
+    #[derive(
+        Debug, Clone, Copy, PartialEq, Eq, strum_macros::EnumString, strum_macros::IntoStaticStr,
+    )]
+    #[strum(serialize_all = "kebab-case")]
     pub enum ImpulseOverriddenBehavior {
         Block,
         None,
     }
 
-    #[derive(Debug, Clone, Copy)]
+    ///Impulses activate for a single sync instead of for as long as the input is held
+    #[derive(
+        Debug, Clone, Copy, PartialEq, Eq, strum_macros::EnumString, strum_macros::IntoStaticStr,
+    )]
+    #[strum(serialize_all = "kebab-case")]
     pub enum Impulse {
         False,
         OnPress,
         OnRelease,
     }
 
-    #[derive(Debug, Clone, Copy)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     pub enum Activator {
         Hold {
             overridden_behavior: OverriddenBehavior,
             impulse: Impulse,
         },
+        ///Fires an impulse when released within `max_hold_duration`
         QuickTap {
             overridden_behavior: ImpulseOverriddenBehavior,
+            ///Delays Hold activators on the same input until the press outlasts `max_hold_duration`
             blocking: bool,
             max_hold_duration: u32,
         },
+        ///Activates on the `taps`th press with at most `duration` between each release and press
         MultiTap {
             overridden_behavior: OverriddenBehavior,
+            ///Delays Hold and QuickTap activators on the same input until another tap can no longer follow
             blocking: bool,
             duration: u32,
             impulse: Impulse,
             taps: u32,
         },
+        ///Activates once the input has been held for `min_hold_duration`
         LongHold {
             min_hold_duration: u32,
             impulse: Impulse,
@@ -61,9 +326,10 @@ mod activator {
     }
 }
 
-mod gyro {
+pub mod gyro {
     ///Defines conversion of angular velocity to delta2d
     ///Y Axis is always controlled by pitch
+    #[derive(Debug, Clone, Copy, PartialEq)]
     pub enum GyroSpace {
         ///Recommended for handheld devices
         LocalSpace {
@@ -76,8 +342,9 @@ mod gyro {
         },
         ///Recommended for controllers
         PlayerSpace {
-            //default 60° for Yaw
-            //default 45° for Roll
+            ///In degrees
+            ///default 60° for Yaw
+            ///default 45° for Roll
             relax_angle: f32,
             x_axis: XAxis,
         },
@@ -86,18 +353,222 @@ mod gyro {
         },
     }
 
-    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    ///Applied to the angular velocity before the sensitivity curve
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct GyroFiltering {
+        ///Speeds below this are ignored
+        pub cut_off_speed: f32,
+        ///Speeds between `cut_off_speed` and `cut_off_speed + cut_off_recovery` are scaled back in gradually
+        pub cut_off_recovery: f32,
+        ///Speeds below this are increasingly smoothed, fully smoothed below half of it
+        pub smooth_threshold: f32,
+        ///Seconds of input averaged when smoothing
+        pub smooth_time: f32,
+    }
+
+    impl Default for GyroFiltering {
+        fn default() -> Self {
+            Self {
+                cut_off_speed: 0.,
+                cut_off_recovery: 0.,
+                smooth_threshold: 0.,
+                smooth_time: 0.125,
+            }
+        }
+    }
+
+    #[derive(
+        Debug, PartialEq, Eq, Clone, Copy, strum_macros::EnumString, strum_macros::IntoStaticStr,
+    )]
+    #[strum(serialize_all = "kebab-case")]
     pub enum XAxis {
         Yaw,
         Roll,
     }
 }
 
-mod chord {
-    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub mod chord {
+    #[derive(
+        Debug, PartialEq, Eq, Clone, Copy, strum_macros::EnumString, strum_macros::IntoStaticStr,
+    )]
+    #[strum(serialize_all = "kebab-case")]
     pub enum ChordType {
+        ///The binding only applies while the modifier is held
         PressToEnable,
+        ///The binding is suppressed while the modifier is held
         PressToDisable,
+        ///Each press of the modifier enables or disables the binding, starting disabled
         Toggle,
     }
 }
+
+pub mod joystick {
+    ///Deadzones and response curve applied to a joystick position, in that order
+    #[derive(Debug, Clone, PartialEq, Default)]
+    pub struct JoystickProcessing {
+        pub deadzone_shape: DeadzoneShape,
+        ///Positions closer to the center than this are ignored
+        pub inner_deadzone: f32,
+        ///Positions closer to the edge than this are treated as fully deflected
+        pub outer_deadzone: f32,
+        ///The smallest output outside of the inner deadzone, to overcome a game's own deadzone
+        pub anti_deadzone: f32,
+        pub curve: ResponseCurve,
+    }
+
+    ///Ramps up the turn rate while the stick is held at the edge
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct EdgeAcceleration {
+        ///How far the stick has to be pushed to count as being at the edge, from 0 to 1
+        pub threshold: f32,
+        ///Seconds until the full multiplier is reached
+        pub ramp_time: f32,
+        pub multiplier: f32,
+    }
+
+    ///Thresholds are distances from the center, from 0 to 1
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum StickRegion {
+        ///The stick is pushed past the threshold within the direction's sector
+        Direction {
+            direction: Direction,
+            sectors: Sectors,
+            threshold: f32,
+        },
+        ///The stick is pushed past the threshold in any direction
+        OuterRing { threshold: f32 },
+        ///The stick is pushed past the deadzone but not past the threshold
+        InnerRing { deadzone: f32, threshold: f32 },
+    }
+
+    #[derive(
+        Debug, Clone, Copy, PartialEq, Eq, strum_macros::EnumString, strum_macros::IntoStaticStr,
+    )]
+    #[strum(serialize_all = "kebab-case")]
+    pub enum Direction {
+        Up,
+        UpRight,
+        Right,
+        DownRight,
+        Down,
+        DownLeft,
+        Left,
+        UpLeft,
+    }
+
+    impl Direction {
+        pub fn is_diagonal(&self) -> bool {
+            matches!(
+                self,
+                Direction::UpRight | Direction::DownRight | Direction::DownLeft | Direction::UpLeft
+            )
+        }
+
+        ///Clockwise from up
+        pub fn angle(&self) -> f32 {
+            let eighths = match self {
+                Direction::Up => 0.,
+                Direction::UpRight => 1.,
+                Direction::Right => 2.,
+                Direction::DownRight => 3.,
+                Direction::Down => 4.,
+                Direction::DownLeft => 5.,
+                Direction::Left => 6.,
+                Direction::UpLeft => 7.,
+            };
+            eighths * std::f32::consts::FRAC_PI_4
+        }
+    }
+
+    ///How the stick is divided into directions
+    #[derive(
+        Debug, Clone, Copy, PartialEq, Eq, strum_macros::EnumString, strum_macros::IntoStaticStr,
+    )]
+    #[strum(serialize_all = "kebab-case")]
+    pub enum Sectors {
+        ///90° sectors, diagonals are not allowed
+        Four,
+        ///45° sectors
+        Eight,
+    }
+
+    #[derive(
+        Debug, Clone, Copy, PartialEq, Eq, strum_macros::EnumString, strum_macros::IntoStaticStr,
+    )]
+    #[strum(serialize_all = "kebab-case")]
+    pub enum StickAxis {
+        ///Right is positive
+        X,
+        ///Up is positive
+        Y,
+    }
+
+    #[derive(
+        Debug,
+        Clone,
+        Copy,
+        PartialEq,
+        Eq,
+        Default,
+        strum_macros::EnumString,
+        strum_macros::IntoStaticStr,
+    )]
+    #[strum(serialize_all = "kebab-case")]
+    pub enum DeadzoneShape {
+        ///Applied to the distance from the center
+        #[default]
+        Radial,
+        ///Applied to each axis separately
+        Axial,
+        ///Small axes are snapped to zero before applying the radial deadzone
+        Hybrid,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default)]
+    pub enum ResponseCurve {
+        #[default]
+        Linear,
+        Power(f32),
+        ///Linearly interpolated `(input, output)` points between 0 and 1, sorted by input
+        ///
+        ///(0, 0) and (1, 1) are implied
+        Piecewise(Vec<(f32, f32)>),
+    }
+}
+
+pub mod touchpad {
+    ///Normalized to 0..1 with (0, 0) at the top left of the touchpad, like touch points
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct TouchpadRegion {
+        pub left: f32,
+        pub top: f32,
+        pub right: f32,
+        pub bottom: f32,
+    }
+
+    impl TouchpadRegion {
+        pub fn contains(&self, x: f32, y: f32) -> bool {
+            (self.left..=self.right).contains(&x) && (self.top..=self.bottom).contains(&y)
+        }
+
+        ///Maps a position on the touchpad to 0..1 within the region
+        pub fn normalize(&self, x: f32, y: f32) -> (f32, f32) {
+            (
+                (x - self.left) / (self.right - self.left),
+                (y - self.top) / (self.bottom - self.top),
+            )
+        }
+    }
+
+    ///The whole touchpad
+    impl Default for TouchpadRegion {
+        fn default() -> Self {
+            Self {
+                left: 0.,
+                top: 0.,
+                right: 1.,
+                bottom: 1.,
+            }
+        }
+    }
+}
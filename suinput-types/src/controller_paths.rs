@@ -63,6 +63,17 @@ pub struct GameControllerPaths {
     pub accel: SuPath,
 
     pub touchpad: SuPath,
+
+    pub led: SuPath,
+    pub player_number: SuPath,
+    pub haptic_left: SuPath,
+    pub haptic_right: SuPath,
+    pub rumble_left: SuPath,
+    pub rumble_right: SuPath,
+    pub left_trigger_rumble: SuPath,
+    pub right_trigger_rumble: SuPath,
+    pub left_adaptive_trigger: SuPath,
+    pub right_adaptive_trigger: SuPath,
 }
 
 impl GameControllerPaths {
@@ -122,6 +133,17 @@ impl GameControllerPaths {
             start: get_path("/input/start/click"),
             options: get_path("/input/options/click"),
             mute: get_path("/input/mute/click"),
+
+            led: get_path("/output/out/led"),
+            player_number: get_path("/output/out/player_number"),
+            haptic_left: get_path("/output/out/haptic_left"),
+            haptic_right: get_path("/output/out/haptic_right"),
+            rumble_left: get_path("/output/out/rumble_left"),
+            rumble_right: get_path("/output/out/rumble_right"),
+            left_trigger_rumble: get_path("/output/trigger_left/rumble"),
+            right_trigger_rumble: get_path("/output/trigger_right/rumble"),
+            left_adaptive_trigger: get_path("/output/trigger_left/adaptive_trigger"),
+            right_adaptive_trigger: get_path("/output/trigger_right/adaptive_trigger"),
         }
     }
 }
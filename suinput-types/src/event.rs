@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use mint::{Vector2, Vector3};
 use thiserror::Error;
 
@@ -21,11 +23,59 @@ pub enum InputComponentEvent {
     Trigger(f32),
     Joystick(Vector2<f32>),
     Move2D(Vector2<f64>),
+    Touchpad(TouchpadState),
+    Cursor(Cursor),
 
     Gyro(Vector3<f32>),
     Accel(Vector3<f32>),
 }
 
+/// The most touch points a single touchpad component can report
+pub const MAX_TOUCH_POINTS: usize = 4;
+
+/// Every touch point of a touchpad, indexed by finger
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct TouchpadState {
+    pub points: [TouchPoint; MAX_TOUCH_POINTS],
+}
+
+impl TouchpadState {
+    /// Returns the index and state of each finger currently touching the pad
+    pub fn down_points(&self) -> impl Iterator<Item = (usize, &TouchPoint)> {
+        self.points
+            .iter()
+            .enumerate()
+            .filter(|(_, point)| point.down)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TouchPoint {
+    pub down: bool,
+    /// Normalized to 0..1 with (0, 0) at the top left of the touchpad
+    pub position: Vector2<f32>,
+    /// None if the touchpad does not report pressure
+    pub pressure: Option<f32>,
+}
+
+impl Default for TouchPoint {
+    fn default() -> Self {
+        Self {
+            down: false,
+            position: Vector2 { x: 0., y: 0. },
+            pressure: None,
+        }
+    }
+}
+
+/// A point within a window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cursor {
+    /// Normalized to 0..1 with (0, 0) at the top left of the window's client area
+    pub normalized_window_coords: Vector2<f64>,
+    pub window: WindowHandle,
+}
+
 impl InputComponentEvent {
     pub fn get_gyro(self) -> Result<Vector3<f32>, ()> {
         match self {
@@ -41,3 +91,48 @@ impl InputComponentEvent {
         }
     }
 }
+
+/// Sent from the runtime to the driver that owns `device`
+#[derive(Debug, Clone, Copy)]
+pub struct OutputEvent {
+    pub device: u64,
+    pub path: SuPath,
+    pub time: Time,
+    pub data: OutputComponentEvent,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputComponentEvent {
+    /// Amplitude is normalized to 0..1, a zero amplitude stops the vibration early
+    Vibration {
+        amplitude: f32,
+        duration: Duration,
+    },
+    /// RGB normalized to 0..1
+    Led(Vector3<f32>),
+    PlayerNumber(u32),
+    AdaptiveTrigger(AdaptiveTriggerEffect),
+}
+
+/// Trigger positions and strengths are normalized to 0..1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AdaptiveTriggerEffect {
+    Off,
+    /// Constant resistance from `start` to the end of the pull
+    Feedback {
+        start: f32,
+        strength: f32,
+    },
+    /// Resistance between `start` and `end` which gives way once passed, like a gun trigger
+    Weapon {
+        start: f32,
+        end: f32,
+        strength: f32,
+    },
+    /// Vibrates from `start` to the end of the pull, frequency is in Hz
+    Vibration {
+        start: f32,
+        amplitude: f32,
+        frequency: f32,
+    },
+}
@@ -1,6 +1,6 @@
 use std::num::NonZeroUsize;
 
-use binding::SimpleBinding;
+use binding::Binding;
 use thiserror::Error;
 
 pub mod action;
@@ -45,7 +45,19 @@ pub enum CreateBindingLayoutError {
     #[error("Bad User Path `{0}`")]
     BadUserPath(String),
     #[error("Bad Binding `{0:?}`")]
-    BadBinding(SimpleBinding),
+    BadBinding(Binding),
+    #[error("Bad Action Name `{0}`")]
+    BadActionName(String),
+    #[error("Bad File {0}")]
+    BadFile(String),
+    #[error("Parse Error {0}")]
+    Parse(String),
+    #[error("{file}:{line}: {error}")]
+    InFile {
+        file: String,
+        line: usize,
+        error: Box<CreateBindingLayoutError>,
+    },
 }
 
 pub type WindowHandle = NonZeroUsize;
@@ -1,8 +1,9 @@
-use crate::{session::SuSession, Inner};
+use crate::{session::SuSession, Inner, SuBindingLayout};
 use std::fmt::{Debug, Formatter};
 use std::num::NonZeroU128;
 use std::path::Path;
 use suinput_core::application_instance::ApplicationInstance;
+use suinput_types::SuPath;
 
 #[derive(Clone)]
 pub struct SuApplicationInstance(
@@ -25,8 +26,31 @@ impl SuApplicationInstance {
         }
     }
 
+    /// Saved under the instance's storage path and layered over the default binding layouts when a session is created
+    pub fn save_binding_override(&self, binding_layout: &SuBindingLayout) -> crate::Result<()> {
+        match &self.0 {
+            Inner::Embedded(inner) => inner.save_binding_override(
+                binding_layout
+                    .0
+                    .get()
+                    .ok_or(crate::Error::ValidationFailure)?,
+            ),
+            Inner::FFI() => todo!(),
+        }
+    }
+
+    pub fn remove_binding_override(&self, interaction_profile: SuPath) -> crate::Result<()> {
+        match &self.0 {
+            Inner::Embedded(inner) => inner.remove_binding_override(interaction_profile),
+            Inner::FFI() => todo!(),
+        }
+    }
+
     pub fn get_persistent_unique_id(&self) -> Option<NonZeroU128> {
-        todo!()
+        match &self.0 {
+            Inner::Embedded(inner) => Some(inner.get_persistent_unique_id()),
+            Inner::FFI() => todo!(),
+        }
     }
 
     pub fn delete_application_instance(&self) {
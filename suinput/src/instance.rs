@@ -2,12 +2,17 @@ use super::*;
 use crate::application_instance::SuApplicationInstance;
 use crate::{Inner, SuActionSet, SuBindingLayout};
 use std::num::NonZeroU128;
+use std::path::Path;
 
 use suinput_core::action_set::ActionSet;
 use suinput_core::instance::BindingLayout;
 pub use suinput_core::types::app::ApplicationInfo;
 use suinput_core::types::app::InternalApplicationInstanceCreateInfo;
-pub use suinput_types::binding::SimpleBinding;
+pub use suinput_types::binding::{
+    activator, chord, gyro, joystick, touchpad, Binding, BooleanBinding, ChordBinding,
+    FlickStickBinding, GyroBinding, JoystickBinding, Sensitivity, SimpleBinding, StickAxisBinding,
+    StickCameraBinding, StickRegionBinding, TouchpadBinding, TriggerBinding,
+};
 pub use suinput_types::CreateBindingLayoutError;
 use suinput_types::SuPath;
 
@@ -39,11 +44,11 @@ impl SuInstance {
         })
     }
 
-    pub fn create_binding_layout(
+    pub fn create_binding_layout<B: Into<Binding> + Clone>(
         &self,
         name: &str,
         interaction_profile: SuPath,
-        bindings: &[SimpleBinding],
+        bindings: &[B],
     ) -> core::result::Result<SuBindingLayout, CreateBindingLayoutError> {
         Ok(SuBindingLayout(match &self.0 {
             Inner::Embedded(inner) => {
@@ -53,6 +58,33 @@ impl SuInstance {
         }))
     }
 
+    pub fn save_binding_layout(
+        &self,
+        binding_layout: &SuBindingLayout,
+        file_path: &Path,
+    ) -> crate::Result<()> {
+        match &self.0 {
+            Inner::Embedded(inner) => inner.save_binding_layout(
+                binding_layout
+                    .0
+                    .get()
+                    .ok_or(crate::Error::ValidationFailure)?,
+                file_path,
+            ),
+            Inner::FFI() => todo!(),
+        }
+    }
+
+    pub fn load_binding_layout(
+        &self,
+        file_path: &Path,
+    ) -> core::result::Result<SuBindingLayout, CreateBindingLayoutError> {
+        Ok(SuBindingLayout(match &self.0 {
+            Inner::Embedded(inner) => Inner::Embedded(inner.load_binding_layout(file_path)?),
+            Inner::FFI() => todo!(),
+        }))
+    }
+
     pub fn create_application_instance(
         &self,
         create_info: &ApplicationInstanceCreateInfo,
@@ -7,12 +7,15 @@ use suinput_core::{action, action_set, user};
 pub use suinput_core::driver_interface;
 pub use suinput_core::driver_interface::RuntimeInterface;
 pub use suinput_core::driver_interface::SuInputDriver;
+pub use suinput_core::runtime::{RuntimeConfig, RuntimeConfigError};
 pub use suinput_core::types::*;
 
 pub use suinput_types::action::ActionEvent;
 pub use suinput_types::action::ActionEventEnum;
 pub use suinput_types::action::ActionListener;
 pub use suinput_types::event::PathFormatError;
+pub use suinput_types::event::{AdaptiveTriggerEffect, Cursor, OutputComponentEvent};
+pub use suinput_types::WindowHandle;
 
 pub mod application_instance;
 pub mod instance;
@@ -50,6 +53,17 @@ pub fn load_runtime() -> runtime::SuInputRuntime {
     runtime::SuInputRuntime(Inner::Embedded(suinput_core::runtime::Runtime::new()))
 }
 
+/// Same as [`load_runtime`] but the embedded runtime also loads the given configuration files
+///
+/// This should only be called once per process
+pub fn load_runtime_with_config(
+    config: RuntimeConfig,
+) -> core::result::Result<runtime::SuInputRuntime, RuntimeConfigError> {
+    Ok(runtime::SuInputRuntime(Inner::Embedded(
+        suinput_core::runtime::Runtime::with_config(config)?,
+    )))
+}
+
 #[derive(Clone)]
 pub struct SuUser(Inner<user::User>);
 
@@ -1,6 +1,7 @@
-use crate::{Inner, SuAction, SuActionSet, SuUser};
-use suinput_core::types::action_type::ActionType;
-use suinput_types::action::ActionListener;
+use crate::{Inner, SuAction, SuActionSet, SuBindingLayout, SuUser};
+pub use suinput_core::session::InputComponentState;
+use suinput_core::types::action_type::{ActionType, Output};
+use suinput_types::{action::ActionListener, event::OutputComponentEvent, SuPath, WindowHandle};
 
 #[derive(Clone)]
 pub struct SuSession(pub(crate) Inner<suinput_core::session::Session>);
@@ -30,6 +31,41 @@ impl SuSession {
         }
     }
 
+    pub fn set_binding_layout(&self, binding_layout: &SuBindingLayout) {
+        match (&self.0, &binding_layout.0) {
+            (Inner::Embedded(inner), Inner::Embedded(binding_layout)) => {
+                inner.set_binding_layout(binding_layout)
+            }
+            (Inner::FFI(), Inner::FFI()) => todo!(),
+            _ => panic!(),
+        }
+    }
+
+    pub fn remove_binding_layout(&self, interaction_profile: SuPath) {
+        match &self.0 {
+            Inner::Embedded(inner) => inner.remove_binding_layout(interaction_profile),
+            Inner::FFI() => todo!(),
+        }
+    }
+
+    pub fn set_window(&self, window: Option<WindowHandle>) {
+        match &self.0 {
+            Inner::Embedded(inner) => inner.set_window(window),
+            Inner::FFI() => todo!(),
+        }
+    }
+
+    pub fn get_input_component_state(
+        &self,
+        user_path: SuPath,
+        input_path: SuPath,
+    ) -> Option<InputComponentState> {
+        match &self.0 {
+            Inner::Embedded(inner) => inner.get_input_component_state(user_path, input_path),
+            Inner::FFI() => todo!(),
+        }
+    }
+
     pub fn get_action_state<T: ActionType>(&self, action: &SuAction<T>) -> Result<T::State, ()> {
         match (&self.0, &action.0) {
             (Inner::Embedded(inner), Inner::Embedded(action)) => {
@@ -48,6 +84,16 @@ impl SuSession {
         }
     }
 
+    pub fn fire_output_action(&self, action: &SuAction<Output>, event: OutputComponentEvent) {
+        match (&self.0, &action.0) {
+            (Inner::Embedded(inner), Inner::Embedded(action)) => {
+                inner.fire_output_action(action, event)
+            }
+            (Inner::FFI(), Inner::FFI()) => todo!(),
+            _ => panic!(),
+        }
+    }
+
     // pub fn create_action_space(&self, action: &SuAction<Pose>, pose_in_space: Pose) {
     //     todo!()
     // }